@@ -15,6 +15,17 @@ pub enum ApplicationError {
     Other(anyhow::Error),
 }
 
+impl ApplicationError {
+    /// Whether `entity` was modified concurrently,
+    /// in which case the same request may succeed if it is retried.
+    pub fn is_conflict(&self, entity: &str) -> bool {
+        matches!(
+            self,
+            Self::Kernel(KernelErrorKind { error: KernelError::Conflict { entity: e, .. }, .. }) if *e == entity
+        )
+    }
+}
+
 impl From<KernelError> for ApplicationError {
     fn from(value: KernelError) -> Self {
         match value {
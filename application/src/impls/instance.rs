@@ -1,4 +1,4 @@
-use crate::services::{CreateEmptyInstanceService, CreateInstanceService, UpdateInstanceService};
+use crate::services::{CreateEmptyInstanceService, CreateInstanceService, DependOnCreateEmptyInstanceService, DependOnUpdateInstanceService, ExpireInstanceService, UpdateInstanceService};
 use kernel::repository::{DependOnInstanceRepository, DependOnLocationRepository};
 
 impl<T> CreateInstanceService for T where T: DependOnInstanceRepository + DependOnLocationRepository {}
@@ -6,3 +6,5 @@ impl<T> CreateInstanceService for T where T: DependOnInstanceRepository + Depend
 impl<T> CreateEmptyInstanceService for T where T: DependOnInstanceRepository + DependOnLocationRepository {}
 
 impl<T> UpdateInstanceService for T where T: DependOnInstanceRepository {}
+
+impl<T> ExpireInstanceService for T where T: DependOnInstanceRepository + DependOnUpdateInstanceService + DependOnCreateEmptyInstanceService {}
@@ -1,7 +1,13 @@
 use crate::services::{
-    CreateRingService, DependOnCreateInstanceService, DependOnUpdateInstanceService,
+    CreateRingService, DeleteRingService, DependOnCreateInstanceService,
+    DependOnUpdateInstanceService, HideRingService, RestoreRingService,
 };
-use kernel::repository::{DependOnInstanceRepository, DependOnLocationRepository};
+use kernel::finder::DependOnRingFinder;
+use kernel::repository::{
+    DependOnImageRepository, DependOnInstanceRepository, DependOnLocationRepository,
+    DependOnRingRepository,
+};
+use kernel::service::DependOnImageExportExternalStorageService;
 
 impl<T> CreateRingService for T where
     T: DependOnInstanceRepository
@@ -10,3 +16,15 @@ impl<T> CreateRingService for T where
         + DependOnUpdateInstanceService
 {
 }
+
+impl<T> HideRingService for T where T: DependOnRingFinder + DependOnRingRepository {}
+
+impl<T> RestoreRingService for T where T: DependOnRingFinder + DependOnRingRepository {}
+
+impl<T> DeleteRingService for T where
+    T: DependOnRingFinder
+        + DependOnRingRepository
+        + DependOnImageRepository
+        + DependOnImageExportExternalStorageService
+{
+}
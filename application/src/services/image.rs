@@ -2,7 +2,7 @@ use crate::error::ApplicationError;
 use crate::transfer::{CreateImageDto, ImageDto};
 use async_trait::async_trait;
 use kernel::entities::image::{Image, ImageBin, MetaData};
-use kernel::entities::ring::{CreatedAt, DestructRing, RingId};
+use kernel::entities::ring::{CreatedAt, DestructRing, RingId, UserId};
 use kernel::finder::{DependOnRingFinder, RingFinder};
 use kernel::repository::{DependOnImageRepository, DependOnLocationRepository, ImageRepository, LocationRepository};
 use kernel::service::{
@@ -25,13 +25,17 @@ pub trait ExportImageService:
     async fn export(&self, export: CreateImageDto) -> Result<ImageDto, ApplicationError> {
         let CreateImageDto {
             id,
+            user,
             bin,
             created_at,
         } = export;
 
         let id = RingId::new(id);
 
-        let Some(ring) = self.ring_finder().find_by_id(&id).await? else {
+        // Rings of other users are treated as missing, so that they can not be probed.
+        let Some(ring) = self.ring_finder().find_by_id(&id).await?
+            .filter(|ring| ring.user() == &UserId::new(user))
+        else {
             return Err(ApplicationError::NotFound {
                 entity: "ring",
                 method: "find_by_id",
@@ -39,7 +43,7 @@ pub trait ExportImageService:
             });
         };
 
-        let binary = ImageBin::from_base64(bin)?;
+        let binary = ImageBin::new(bin);
         let created_at = CreatedAt::new(created_at);
         let image = Image::new(id, binary, created_at);
         let DestructRing {
@@ -1,7 +1,10 @@
 use crate::error::ApplicationError;
+use crate::transfer::{ExpiredDto, RolloverDto};
 use async_trait::async_trait;
-use kernel::entities::instance::Instance;
-use kernel::repository::{DependOnInstanceRepository, DependOnLocationRepository, InstanceRepository};
+use kernel::entities::instance::{Expiry, FinishedAt, Instance, InstanceId, RingSet, StartedAt, Version};
+use kernel::external::time::OffsetDateTime;
+use kernel::entities::location::LocationId;
+use kernel::repository::{DependOnInstanceRepository, DependOnLocationRepository, InstanceRepository, LocationRepository};
 use orbital::export_service;
 
 #[async_trait]
@@ -23,8 +26,23 @@ pub trait CreateEmptyInstanceService:
     'static + Sync + Send + DependOnLocationRepository +  DependOnInstanceRepository
 {
     // noinspection DuplicatedCode
-    async fn create(&self) -> Result<Instance, ApplicationError> {
-        let instance = Instance::default();
+    async fn create(&self, location: &LocationId) -> Result<Instance, ApplicationError> {
+        let Some(location) = self.location_repository().find_by_id(location).await? else {
+            return Err(ApplicationError::NotFound {
+                entity: "location",
+                method: "CreateEmptyInstanceService::create",
+                target: location.to_string(),
+            });
+        };
+
+        let instance = Instance::new(
+            InstanceId::default(),
+            *location.id(),
+            RingSet::new(*location.capacity(), Vec::new())?,
+            StartedAt::default(),
+            FinishedAt::default(),
+            Version::default(),
+        );
         self.instance_repository().create(&instance).await?;
 
         Ok(instance)
@@ -34,6 +52,9 @@ pub trait CreateEmptyInstanceService:
 #[async_trait]
 #[export_service]
 pub trait UpdateInstanceService: 'static + Sync + Send + DependOnInstanceRepository {
+    /// Store `update` based on the version it was read at.
+    ///
+    /// Fails with `Conflict` if the instance has been updated since then.
     async fn update(&self, update: Instance) -> Result<Instance, ApplicationError> {
         let Some(before) = self.instance_repository().find_by_id(update.id()).await? else {
             return Err(ApplicationError::NotFound {
@@ -47,10 +68,73 @@ pub trait UpdateInstanceService: 'static + Sync + Send + DependOnInstanceReposit
         let instance = before.reconstruct(|dest| {
             dest.rings = after.rings;
             dest.finished_at = after.finished_at;
+            dest.version = after.version;
         });
 
         self.instance_repository().update(&instance).await?;
 
-        Ok(instance)
+        Ok(instance.reconstruct(|dest| dest.version = dest.version.next()))
+    }
+}
+
+#[async_trait]
+#[export_service]
+pub trait ExpireInstanceService:
+    'static
+    + Sync
+    + Send
+    + DependOnInstanceRepository
+    + DependOnUpdateInstanceService
+    + DependOnCreateEmptyInstanceService
+{
+    /// Finish every unfinished instance that has passed `expiry`, and start a new one at its location.
+    ///
+    /// An error on one instance does not stop the others. It is returned along with the rollovers,
+    /// and the instance is looked at again next time if it is still unfinished.
+    async fn expire(&self, expiry: &Expiry) -> Result<ExpiredDto, ApplicationError> {
+        let now = OffsetDateTime::now_utc();
+        let mut rollovers = Vec::new();
+        let mut errors = Vec::new();
+
+        for instance in self.instance_repository().find_all_unfinished().await? {
+            if !expiry.is_expired(&instance, now) {
+                continue;
+            }
+
+            let location = *instance.location();
+            let instance = instance.reconstruct(|dest| dest.finished_at = FinishedAt::new(now));
+            let finished = match self.update_instance_service().update(instance).await {
+                Ok(finished) => finished,
+                // A ring has been placed in the meantime, so it is looked at again next time.
+                Err(e) if e.is_conflict("instance") => continue,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+
+            // The instance is finished by now, so it is announced even if the next one fails to start.
+            let started = match CreateEmptyInstanceService::create(self.create_empty_instance_service(), &location).await {
+                Ok(started) => Some(started),
+                // A ring request has already started the next one.
+                Err(e) if e.is_conflict("instance") => {
+                    match self.instance_repository().find_unfinished(&location).await {
+                        Ok(started) => started,
+                        Err(e) => {
+                            errors.push(e.into());
+                            None
+                        }
+                    }
+                }
+                Err(e) => {
+                    errors.push(e);
+                    None
+                }
+            };
+
+            rollovers.push(RolloverDto { finished, started });
+        }
+
+        Ok(ExpiredDto { rollovers, errors })
     }
 }
@@ -1,8 +1,11 @@
 use crate::error::ApplicationError;
 use crate::transfer::{CreateLocationDto, DeleteLocationDto, LocationDto, UpdateLocationDto};
 use async_trait::async_trait;
+use kernel::entities::audit::{AuditAction, AuditEntry};
 use kernel::entities::geology::{Position, Radius};
+use kernel::entities::instance::Capacity;
 use kernel::entities::location::{Localize, LocalizeId, Location, LocationId};
+use kernel::entities::token::AdminKeyId;
 use kernel::repository::{DependOnLocationRepository, LocationRepository};
 use orbital::export_service;
 use kernel::entities::volatiles::Etag;
@@ -19,23 +22,27 @@ pub trait CreateLocationService:
 {
     async fn create(&self, create: CreateLocationDto) -> Result<LocationDto, ApplicationError> {
         let CreateLocationDto {
+            actor,
             latitude,
             longitude,
             radius,
+            capacity,
             localize,
         } = create;
 
         let lid = LocationId::default();
         let pos = Position::new(longitude, latitude)?;
         let rad = Radius::new(radius);
+        let capacity = capacity.map(Capacity::new).transpose()?.unwrap_or_default();
         let loc = localize
             .into_iter()
             .map(|(c, n)| Localize::new(c, n))
             .collect::<Result<Vec<Localize>, _>>()?;
 
-        let mark = Location::new(lid, pos, rad, loc);
+        let mark = Location::new(lid, pos, rad, capacity, loc);
 
-        self.location_repository().create(&mark).await?;
+        let entry = AuditEntry::record(AdminKeyId::new(actor), AuditAction::LocationCreate, *mark.id(), None, Some(&mark))?;
+        self.location_repository().create(&mark, &entry).await?;
         self.location_e_tag_cache().save(Etag::default()).await?;
 
         Ok(mark.into())
@@ -54,10 +61,12 @@ pub trait UpdateLocationService:
     //noinspection DuplicatedCode
     async fn update(&self, update: UpdateLocationDto) -> Result<LocationDto, ApplicationError> {
         let UpdateLocationDto {
+            actor,
             id,
             latitude,
             longitude,
             radius,
+            capacity,
             localize,
         } = update;
 
@@ -70,10 +79,14 @@ pub trait UpdateLocationService:
             });
         };
 
+        let before = mark.clone();
         let mut mark = mark.into_destruct();
 
         mark.pos = Position::new(longitude, latitude)?;
         mark.rad = Radius::new(radius);
+        if let Some(capacity) = capacity {
+            mark.capacity = Capacity::new(capacity)?;
+        }
         mark.localize = localize
             .into_iter()
             .map(|(c, n)| Localize::new(c, n))
@@ -81,7 +94,8 @@ pub trait UpdateLocationService:
 
         let mark = mark.freeze();
 
-        self.location_repository().update(&mark).await?;
+        let entry = AuditEntry::record(AdminKeyId::new(actor), AuditAction::LocationUpdate, *mark.id(), Some(&before), Some(&mark))?;
+        self.location_repository().update(&mark, &entry).await?;
         self.location_e_tag_cache().save(Etag::default()).await?;
 
         Ok(mark.into())
@@ -99,7 +113,7 @@ pub trait DeleteLocationService:
 {
     //noinspection DuplicatedCode
     async fn delete(&self, delete: DeleteLocationDto) -> Result<(), ApplicationError> {
-        let DeleteLocationDto { id, localize } = delete;
+        let DeleteLocationDto { actor, id, localize } = delete;
 
         let lid = LocationId::new(id);
         let Some(mark) = self.location_repository().find_by_id(&lid).await? else {
@@ -110,14 +124,22 @@ pub trait DeleteLocationService:
             });
         };
 
+        let actor = AdminKeyId::new(actor);
         match localize {
             Some(loc) => {
                 let loc = LocalizeId::new(loc)?;
+                let mut after = mark.clone().into_destruct();
+                after.localize.retain(|localize| localize.country() != &loc);
+                let after = after.freeze();
+                let entry = AuditEntry::record(actor, AuditAction::LocalizeDelete, *mark.id(), Some(&mark), Some(&after))?;
                 self.location_repository()
-                    .delete_localize(mark.id(), &loc)
-                    .await?
+                    .delete_localize(mark.id(), &loc, &entry)
+                    .await?;
+            }
+            None => {
+                let entry = AuditEntry::record(actor, AuditAction::LocationDelete, *mark.id(), Some(&mark), None)?;
+                self.location_repository().delete(mark.id(), &entry).await?;
             }
-            None => self.location_repository().delete(mark.id()).await?,
         }
         self.location_e_tag_cache().save(Etag::default()).await?;
 
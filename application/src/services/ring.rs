@@ -3,20 +3,29 @@ use crate::services::{
     CreateInstanceService, DependOnCreateInstanceService, DependOnUpdateInstanceService,
     UpdateInstanceService,
 };
-use crate::transfer::{CreateRingDto, RingDto};
+use crate::transfer::{CreateRingDto, ModeratedRingDto, RingDto};
 use async_trait::async_trait;
 use kernel::external::time::OffsetDateTime;
 use kernel::{
     entities::{
         geology::Position,
-        instance::{FinishedAt, Instance, InstanceId, RingSet, StartedAt},
+        instance::{FinishedAt, Instance, InstanceId, RingSet, StartedAt, Version},
         location::LocationId,
-        ring::{CreatedAt, HueColor, Index, Ring, RingId, UserId},
+        ring::{CreatedAt, HiddenAt, HueColor, Index, Ring, RingId, UserId},
     },
     repository::{DependOnInstanceRepository, DependOnLocationRepository, InstanceRepository},
 };
 use orbital::export_service;
-use kernel::repository::LocationRepository;
+use kernel::external::uuid::Uuid;
+use kernel::finder::{DependOnRingFinder, RingFinder};
+use kernel::entities::audit::{AuditAction, AuditEntry};
+use kernel::entities::token::AdminKeyId;
+use kernel::repository::{DependOnImageRepository, DependOnRingRepository, ImageRepository, LocationRepository, RingRepository};
+use kernel::service::{DependOnImageExportExternalStorageService, ImageExportExternalStorageService};
+
+/// How many times [`CreateRingService::create`] tries again,
+/// when the instance was modified by another request in the meantime.
+const CONFLICT_RETRY_LIMIT: usize = 3;
 
 
 #[async_trait]
@@ -36,30 +45,15 @@ pub trait CreateRingService:
             longitude,
             latitude,
             indexed,
+            allocation,
             hue,
             user,
             created_at,
         } = create;
 
-        let instance = if let Some(instance) = self
-            .instance_repository()
-            .find_unfinished()
-            .await?
-        {
-            instance
-        } else {
-            let id = InstanceId::default();
-            let rings = RingSet::default();
-            let started_at = StartedAt::default();
-            let finished_at = FinishedAt::default();
-            let instance = Instance::new(id, rings, started_at, finished_at);
-
-            self.create_instance_service().create(instance).await?
-        };
-
         let location = LocationId::new(location);
 
-        let Some(_) = self.location_repository().find_by_id(&location).await? else {
+        let Some(mark) = self.location_repository().find_by_id(&location).await? else {
             return Err(ApplicationError::NotFound {
                 method: "CreateRingService::create",
                 entity: "location",
@@ -67,27 +61,145 @@ pub trait CreateRingService:
             });
         };
 
-        let id = RingId::default();
         let pos = Position::new(longitude, latitude)?;
-        let index = Index::new(indexed)?;
+        mark.verify_within(&pos)?;
+
+        let id = RingId::default();
+        let indexed = indexed.map(Index::new).transpose()?;
         let hue = HueColor::new(hue);
         let address = UserId::new(user);
         let created_at = CreatedAt::new(created_at);
 
-        let ring = Ring::new(id, pos, location, address, index, hue, created_at);
+        let mut attempts = 0;
+        let (instance, ring) = loop {
+            attempts += 1;
 
-        let mut instance = instance.into_destruct();
+            let appended = async {
+                let instance = if let Some(instance) = self
+                    .instance_repository()
+                    .find_unfinished(&location)
+                    .await?
+                {
+                    instance
+                } else {
+                    let id = InstanceId::default();
+                    let rings = RingSet::new(*mark.capacity(), Vec::new())?;
+                    let started_at = StartedAt::default();
+                    let finished_at = FinishedAt::default();
+                    let version = Version::default();
+                    let instance = Instance::new(id, location, rings, started_at, finished_at, version);
 
-        instance.rings.add(ring.clone())?;
+                    self.create_instance_service().create(instance).await?
+                };
 
-        if instance.rings.len() >= 70 {
-            instance.finished_at = FinishedAt::new(OffsetDateTime::now_utc());
-        }
+                let mut instance = instance.into_destruct();
+
+                let index = match indexed {
+                    Some(index) => index,
+                    None => instance.rings.allocate(allocation)?,
+                };
+                let ring = Ring::new(id.clone(), pos, location, address, index, hue, created_at, HiddenAt::default());
+
+                instance.rings.add(ring.clone())?;
 
-        let instance = instance.freeze();
-        let instance = self.update_instance_service().update(instance).await?;
+                if instance.rings.is_filled() {
+                    instance.finished_at = FinishedAt::new(OffsetDateTime::now_utc());
+                }
+
+                let instance = instance.freeze();
+                let instance = self.update_instance_service().update(instance).await?;
+                Ok::<_, ApplicationError>((instance, ring))
+            }
+            .await;
+
+            match appended {
+                Err(e) if e.is_conflict("instance") && attempts <= CONFLICT_RETRY_LIMIT => continue,
+                appended => break appended?,
+            }
+        };
 
         Ok((instance, ring).into())
     }
 }
 
+/// Take down a ring without deleting it.
+///
+/// The ring keeps its index in the instance, and can be restored by [`RestoreRingService`].
+#[async_trait]
+#[export_service]
+pub trait HideRingService: 'static + Sync + Send + DependOnRingFinder + DependOnRingRepository {
+    async fn hide(&self, actor: Uuid, id: Uuid) -> Result<ModeratedRingDto, ApplicationError> {
+        let id = RingId::new(id);
+        let Some(ring) = self.ring_finder().find_by_id(&id).await? else {
+            return Err(ApplicationError::NotFound {
+                entity: "ring",
+                method: "HideRingService::hide",
+                target: id.to_string(),
+            });
+        };
+
+        let before = ring.clone();
+        let ring = ring.reconstruct(|dest| dest.hidden_at = HiddenAt::new(OffsetDateTime::now_utc()));
+        let entry = AuditEntry::record(AdminKeyId::new(actor), AuditAction::RingHide, id, Some(&before), Some(&ring))?;
+        self.ring_repository().update(&ring, &entry).await?;
+
+        Ok(ring.into())
+    }
+}
+
+#[async_trait]
+#[export_service]
+pub trait RestoreRingService: 'static + Sync + Send + DependOnRingFinder + DependOnRingRepository {
+    async fn restore(&self, actor: Uuid, id: Uuid) -> Result<ModeratedRingDto, ApplicationError> {
+        let id = RingId::new(id);
+        let Some(ring) = self.ring_finder().find_by_id(&id).await? else {
+            return Err(ApplicationError::NotFound {
+                entity: "ring",
+                method: "RestoreRingService::restore",
+                target: id.to_string(),
+            });
+        };
+
+        let before = ring.clone();
+        let ring = ring.reconstruct(|dest| dest.hidden_at = HiddenAt::default());
+        let entry = AuditEntry::record(AdminKeyId::new(actor), AuditAction::RingRestore, id, Some(&before), Some(&ring))?;
+        self.ring_repository().update(&ring, &entry).await?;
+
+        Ok(ring.into())
+    }
+}
+
+/// Delete a ring along with its image, which frees its index in the instance.
+#[async_trait]
+#[export_service]
+pub trait DeleteRingService:
+    'static
+    + Sync
+    + Send
+    + DependOnRingFinder
+    + DependOnRingRepository
+    + DependOnImageRepository
+    + DependOnImageExportExternalStorageService
+{
+    async fn delete(&self, actor: Uuid, id: Uuid) -> Result<ModeratedRingDto, ApplicationError> {
+        let id = RingId::new(id);
+        let Some(ring) = self.ring_finder().find_by_id(&id).await? else {
+            return Err(ApplicationError::NotFound {
+                entity: "ring",
+                method: "DeleteRingService::delete",
+                target: id.to_string(),
+            });
+        };
+
+        // The public objects go first, taking down the content is what matters here.
+        if let Some(image) = self.image_repository().find_by_id(&id).await? {
+            self.image_export_external_storage_service().delete(&image).await?;
+        }
+
+        // `images` row is removed by cascade.
+        let entry = AuditEntry::record(AdminKeyId::new(actor), AuditAction::RingDelete, id.clone(), Some(&ring), None)?;
+        self.ring_repository().delete(&id, &entry).await?;
+
+        Ok(ring.into())
+    }
+}
@@ -1,5 +1,6 @@
 mod image;
+mod instance;
 mod location;
 mod ring;
 
-pub use self::{image::*, location::*, ring::*};
+pub use self::{image::*, instance::*, location::*, ring::*};
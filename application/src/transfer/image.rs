@@ -25,6 +25,8 @@ impl From<Image> for ImageDto {
 
 pub struct CreateImageDto {
     pub id: Uuid,
-    pub bin: String,
+    /// Author of the ring, only they can attach an image to it.
+    pub user: Uuid,
+    pub bin: Vec<u8>,
     pub created_at: OffsetDateTime,
 }
@@ -0,0 +1,16 @@
+use crate::error::ApplicationError;
+use kernel::entities::instance::Instance;
+
+/// An instance closed by expiry, and the one started in its place.
+pub struct RolloverDto {
+    pub finished: Instance,
+    /// `None` if the next one could not be started, then the first ring request starts it.
+    pub started: Option<Instance>,
+}
+
+/// Result of a run of [`ExpireInstanceService`](crate::services::ExpireInstanceService).
+pub struct ExpiredDto {
+    pub rollovers: Vec<RolloverDto>,
+    /// Errors on the instances that could not be rolled over.
+    pub errors: Vec<ApplicationError>,
+}
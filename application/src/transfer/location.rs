@@ -8,6 +8,7 @@ pub struct LocationDto {
     pub latitude: f64,
     pub longitude: f64,
     pub radius: i32,
+    pub capacity: i32,
     pub localize: Vec<(String, String)>,
 }
 
@@ -17,6 +18,7 @@ impl From<Location> for LocationDto {
             id,
             pos,
             rad,
+            capacity,
             localize,
         } = value.into_destruct();
         let DestructPosition { x, y } = pos.into_destruct();
@@ -25,6 +27,7 @@ impl From<Location> for LocationDto {
             latitude: y.into(),
             longitude: x.into(),
             radius: rad.into(),
+            capacity: capacity.into(),
             localize: localize
                 .into_iter()
                 .map(|loc| loc.into_destruct())
@@ -36,23 +39,31 @@ impl From<Location> for LocationDto {
 
 #[derive(Debug)]
 pub struct CreateLocationDto {
+    /// The admin key the request was made with.
+    pub actor: Uuid,
     pub latitude: f64,
     pub longitude: f64,
     pub radius: i32,
+    pub capacity: Option<i32>,
     pub localize: Vec<(String, String)>,
 }
 
 #[derive(Debug)]
 pub struct UpdateLocationDto {
+    /// The admin key the request was made with.
+    pub actor: Uuid,
     pub id: Uuid,
     pub latitude: f64,
     pub longitude: f64,
     pub radius: i32,
+    pub capacity: Option<i32>,
     pub localize: Vec<(String, String)>,
 }
 
 #[derive(Debug)]
 pub struct DeleteLocationDto {
+    /// The admin key the request was made with.
+    pub actor: Uuid,
     pub id: Uuid,
     pub localize: Option<String>,
 }
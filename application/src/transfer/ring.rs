@@ -1,5 +1,5 @@
 use kernel::entities::geology::DestructPosition;
-use kernel::entities::instance::{DestructInstance, Instance};
+use kernel::entities::instance::{Allocation, DestructInstance, Instance};
 use kernel::entities::ring::{DestructRing, Ring};
 use kernel::external::time::OffsetDateTime;
 use kernel::external::uuid::Uuid;
@@ -14,12 +14,14 @@ pub struct RingDto {
     pub hue: i32,
     pub user: Uuid,
     pub created_at: OffsetDateTime,
+    /// Whether this ring filled up the instance.
+    pub finished: bool,
 }
 
 impl From<(Instance, Ring)> for RingDto {
     fn from(value: (Instance, Ring)) -> Self {
         let (instance, ring) = value;
-        let DestructInstance { id, .. } = instance.into_destruct();
+        let DestructInstance { id, finished_at, .. } = instance.into_destruct();
         let instance_id = id;
         let DestructRing {
             id,
@@ -29,6 +31,7 @@ impl From<(Instance, Ring)> for RingDto {
             indexed,
             hue,
             created_at,
+            ..
         } = ring.into_destruct();
         let DestructPosition { x, y } = pos_in.into_destruct();
         Self {
@@ -41,6 +44,7 @@ impl From<(Instance, Ring)> for RingDto {
             hue: hue.into(),
             user: user.into(),
             created_at: created_at.into(),
+            finished: finished_at.is_finished(),
         }
     }
 }
@@ -49,8 +53,45 @@ pub struct CreateRingDto {
     pub location: Uuid,
     pub longitude: f64,
     pub latitude: f64,
+    /// A specific slot to place the ring, otherwise it is chosen by `allocation`.
+    pub indexed: Option<i32>,
+    pub allocation: Allocation,
+    pub hue: i32,
+    pub user: Uuid,
+    pub created_at: OffsetDateTime,
+}
+
+/// A ring after a moderation action, without the instance it belongs to.
+pub struct ModeratedRingDto {
+    pub id: Uuid,
+    pub location: Uuid,
     pub indexed: i32,
     pub hue: i32,
     pub user: Uuid,
     pub created_at: OffsetDateTime,
+    pub hidden: bool,
+}
+
+impl From<Ring> for ModeratedRingDto {
+    fn from(value: Ring) -> Self {
+        let DestructRing {
+            id,
+            location,
+            user,
+            indexed,
+            hue,
+            created_at,
+            hidden_at,
+            ..
+        } = value.into_destruct();
+        Self {
+            id: id.into(),
+            location: location.into(),
+            indexed: indexed.into(),
+            hue: hue.into(),
+            user: user.into(),
+            created_at: created_at.into(),
+            hidden: hidden_at.is_hidden(),
+        }
+    }
 }
@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use application::error::ApplicationError;
+use application::services::{CreateRingService, DependOnCreateInstanceService, DependOnCreateRingService, DependOnUpdateInstanceService};
+use application::transfer::CreateRingDto;
+use kernel::entities::geology::{Position, Radius};
+use kernel::entities::instance::{Allocation, Capacity, Instance};
+use kernel::entities::location::{Localize, Location, LocationId};
+use kernel::error::KernelError;
+use kernel::external::time::OffsetDateTime;
+use kernel::external::uuid::Uuid;
+use kernel::repository::{DependOnInstanceRepository, DependOnLocationRepository, MockInstanceRepository, MockLocationRepository};
+
+const CAPACITY: i32 = 8;
+const REQUESTS: i32 = 64;
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 8)]
+async fn main() -> anyhow::Result<()> {
+    let location = Location::new(
+        LocationId::default(),
+        Position::new(132.76661710012877f64, 33.841405349477995f64)?,
+        Radius::new(100),
+        Capacity::new(CAPACITY)?,
+        vec![Localize::new("jp", "愛媛県庁第一別館")?],
+    );
+
+    let mut loc = MockLocationRepository::default();
+    let found = location.clone();
+    loc.expect_find_by_id()
+        .returning(move |_| {
+            let found = found.clone();
+            Box::pin(async move { Ok(Some(found)) })
+        });
+
+    // Behaves like the database: at most one unfinished instance per location,
+    // and an update is rejected unless it is based on the latest version.
+    let mut ins = MockInstanceRepository::default();
+    let stored: Arc<Mutex<Vec<Instance>>> = Arc::new(Mutex::new(Vec::new()));
+    let state = Arc::clone(&stored);
+    ins.expect_find_unfinished()
+        .returning(move |_| {
+            let found = state.lock().unwrap().iter()
+                .find(|i| !i.finished_at().is_finished())
+                .cloned();
+            Box::pin(async move {
+                tokio::task::yield_now().await;
+                Ok(found)
+            })
+        });
+    let state = Arc::clone(&stored);
+    ins.expect_find_by_id()
+        .returning(move |id| {
+            let found = state.lock().unwrap().iter()
+                .find(|i| i.id() == id)
+                .cloned();
+            Box::pin(async move { Ok(found) })
+        });
+    let state = Arc::clone(&stored);
+    ins.expect_create()
+        .returning(move |i| {
+            let mut stored = state.lock().unwrap();
+            let res = if stored.iter().any(|s| !s.finished_at().is_finished()) {
+                Err(KernelError::Conflict { entity: "instance", msg: "unfinished instance already exists." })
+            } else {
+                stored.push(i.clone());
+                Ok(())
+            };
+            Box::pin(async move { res })
+        });
+    let state = Arc::clone(&stored);
+    ins.expect_update()
+        .returning(move |i| {
+            let mut stored = state.lock().unwrap();
+            let current = stored.iter_mut().find(|s| s.id() == i.id()).expect("updated instance should exist.");
+            let res = if current.version() != i.version() {
+                Err(KernelError::Conflict { entity: "instance", msg: "version mismatch." })
+            } else {
+                *current = i.clone().reconstruct(|dest| dest.version = dest.version.next());
+                Ok(())
+            };
+            Box::pin(async move { res })
+        });
+
+    let handler = Arc::new(Handler { loc, ins });
+
+    let tasks = (0..REQUESTS)
+        .map(|n| {
+            let handler = Arc::clone(&handler);
+            let post = CreateRingDto {
+                location: Uuid::from(*location.id()),
+                longitude: 132.7666,
+                latitude: 33.8414,
+                indexed: (n % 2 == 0).then_some(n % CAPACITY),
+                allocation: Allocation::Random,
+                hue: n,
+                user: Uuid::new_v4(),
+                created_at: OffsetDateTime::now_utc(),
+            };
+            tokio::spawn(async move { handler.create_ring_service().create(post).await })
+        })
+        .collect::<Vec<_>>();
+
+    let mut accepted = 0;
+    for task in tasks {
+        match task.await? {
+            Ok(_) => accepted += 1,
+            Err(ApplicationError::Kernel(kind)) if matches!(kind.error, KernelError::Conflict { .. }) => {},
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+    }
+
+    let stored = stored.lock().unwrap();
+    assert!(accepted > 0);
+    assert_eq!(stored.iter().map(|i| i.rings().len()).sum::<usize>(), accepted);
+    assert!(stored.iter().filter(|i| !i.finished_at().is_finished()).count() <= 1);
+    for instance in stored.iter() {
+        assert!(instance.rings().len() <= CAPACITY as usize);
+        let indexes = instance.rings().iter().map(|r| *r.indexed().as_ref()).collect::<HashSet<_>>();
+        assert_eq!(indexes.len(), instance.rings().len());
+        assert_eq!(instance.finished_at().is_finished(), instance.rings().is_filled());
+    }
+
+    Ok(())
+}
+
+pub struct Handler {
+    loc: MockLocationRepository,
+    ins: MockInstanceRepository,
+}
+
+impl DependOnLocationRepository for Handler {
+    type LocationRepository = MockLocationRepository;
+    fn location_repository(&self) -> &Self::LocationRepository {
+        &self.loc
+    }
+}
+
+impl DependOnInstanceRepository for Handler {
+    type InstanceRepository = MockInstanceRepository;
+    fn instance_repository(&self) -> &Self::InstanceRepository {
+        &self.ins
+    }
+}
+
+impl DependOnCreateRingService for Handler {
+    type CreateRingService = Self;
+    fn create_ring_service(&self) -> &Self::CreateRingService {
+        self
+    }
+}
+
+impl DependOnCreateInstanceService for Handler {
+    type CreateInstanceService = Self;
+    fn create_instance_service(&self) -> &Self::CreateInstanceService {
+        self
+    }
+}
+
+impl DependOnUpdateInstanceService for Handler {
+    type UpdateInstanceService = Self;
+    fn update_instance_service(&self) -> &Self::UpdateInstanceService {
+        self
+    }
+}
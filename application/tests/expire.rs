@@ -0,0 +1,133 @@
+use application::services::{DependOnCreateEmptyInstanceService, DependOnExpireInstanceService, DependOnUpdateInstanceService, ExpireInstanceService};
+use kernel::entities::geology::{Position, Radius};
+use kernel::entities::instance::{Capacity, Expiry, FinishedAt, Instance, InstanceId, RingSet, StartedAt, Version};
+use kernel::entities::location::{Localize, Location, LocationId};
+use kernel::entities::ring::{CreatedAt, HiddenAt, HueColor, Index, Ring, RingId, UserId};
+use kernel::error::KernelError;
+use kernel::external::time::{Duration, OffsetDateTime};
+use kernel::repository::{DependOnInstanceRepository, DependOnLocationRepository, MockInstanceRepository, MockLocationRepository};
+
+fn expired(location: &Location) -> anyhow::Result<Instance> {
+    let started = OffsetDateTime::now_utc() - Duration::hours(2);
+    let ring = Ring::new(
+        RingId::default(),
+        *location.pos(),
+        *location.id(),
+        UserId::default(),
+        Index::new(0)?,
+        HueColor::new(0),
+        CreatedAt::new(started),
+        HiddenAt::default(),
+    );
+    Ok(Instance::new(
+        InstanceId::default(),
+        *location.id(),
+        RingSet::new(*location.capacity(), vec![ring])?,
+        StartedAt::new(started),
+        FinishedAt::default(),
+        Version::default(),
+    ))
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let location = Location::new(
+        LocationId::default(),
+        Position::new(132.76661710012877f64, 33.841405349477995f64)?,
+        Radius::new(100),
+        Capacity::new(12)?,
+        vec![Localize::new("jp", "愛媛県庁第一別館")?],
+    );
+
+    let mut loc = MockLocationRepository::default();
+    let found = location.clone();
+    loc.expect_find_by_id()
+        .returning(move |_| {
+            let found = found.clone();
+            Box::pin(async move { Ok(Some(found)) })
+        });
+
+    // The first instance fails to be finished, the second one is rolled over.
+    let broken = expired(&location)?;
+    let instances = vec![broken.clone(), expired(&location)?];
+
+    let mut ins = MockInstanceRepository::default();
+    let all = instances.clone();
+    ins.expect_find_all_unfinished()
+        .returning(move || {
+            let all = all.clone();
+            Box::pin(async move { Ok(all) })
+        });
+    let all = instances.clone();
+    ins.expect_find_by_id()
+        .returning(move |id| {
+            let found = all.iter().find(|i| i.id() == id).cloned();
+            Box::pin(async move { Ok(found) })
+        });
+    let broken_id = broken.id().clone();
+    ins.expect_update()
+        .returning(move |i| {
+            let res = if i.id() == &broken_id {
+                Err(KernelError::Driver(anyhow::Error::msg("connection reset.")))
+            } else {
+                Ok(())
+            };
+            Box::pin(async move { res })
+        });
+    ins.expect_create()
+        .returning(|_| Box::pin(async { Ok(()) }));
+
+    let handler = Handler { loc, ins };
+
+    let expired = handler.expire_instance_service()
+        .expire(&Expiry::new(Duration::hours(1), None))
+        .await?;
+    assert_eq!(expired.errors.len(), 1);
+    assert_eq!(expired.rollovers.len(), 1);
+    let rollover = &expired.rollovers[0];
+    assert_ne!(rollover.finished.id(), broken.id());
+    assert!(rollover.finished.finished_at().is_finished());
+    assert!(rollover.started.is_some());
+
+    Ok(())
+}
+
+pub struct Handler {
+    loc: MockLocationRepository,
+    ins: MockInstanceRepository,
+}
+
+impl DependOnLocationRepository for Handler {
+    type LocationRepository = MockLocationRepository;
+    fn location_repository(&self) -> &Self::LocationRepository {
+        &self.loc
+    }
+}
+
+impl DependOnInstanceRepository for Handler {
+    type InstanceRepository = MockInstanceRepository;
+    fn instance_repository(&self) -> &Self::InstanceRepository {
+        &self.ins
+    }
+}
+
+impl DependOnExpireInstanceService for Handler {
+    type ExpireInstanceService = Self;
+    fn expire_instance_service(&self) -> &Self::ExpireInstanceService {
+        self
+    }
+}
+
+impl DependOnUpdateInstanceService for Handler {
+    type UpdateInstanceService = Self;
+    fn update_instance_service(&self) -> &Self::UpdateInstanceService {
+        self
+    }
+}
+
+impl DependOnCreateEmptyInstanceService for Handler {
+    type CreateEmptyInstanceService = Self;
+    fn create_empty_instance_service(&self) -> &Self::CreateEmptyInstanceService {
+        self
+    }
+}
@@ -0,0 +1,126 @@
+use application::services::{DeleteLocationService, DependOnDeleteLocationService, DependOnUpdateLocationService, UpdateLocationService};
+use application::transfer::{DeleteLocationDto, UpdateLocationDto};
+use kernel::entities::audit::AuditAction;
+use kernel::entities::geology::{Position, Radius};
+use kernel::entities::instance::Capacity;
+use kernel::entities::location::{Localize, Location, LocationId};
+use kernel::external::uuid::Uuid;
+use kernel::repository::{DependOnLocationRepository, MockLocationRepository};
+use kernel::volatiles::{DependOnLocationETagCache, MockLocationETagCache};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let location = Location::new(
+        LocationId::default(),
+        Position::new(132.76661710012877f64, 33.841405349477995f64)?,
+        Radius::new(100),
+        Capacity::new(12)?,
+        vec![
+            Localize::new("jp", "愛媛県庁第一別館")?,
+            Localize::new("en", "Ehime Prefecture Office Branch Office")?,
+        ],
+    );
+    let actor = Uuid::new_v4();
+
+    let mut loc = MockLocationRepository::default();
+    let found = location.clone();
+    loc.expect_find_by_id()
+        .returning(move |_| {
+            let found = found.clone();
+            Box::pin(async move { Ok(Some(found)) })
+        });
+    // The entry is written by the repository along with the change.
+    loc.expect_update()
+        .withf(move |update, entry| {
+            *entry.action() == AuditAction::LocationUpdate
+                && Uuid::from(*entry.actor()) == actor
+                && entry.before().as_ref().map(|before| &before["rad"]) != entry.after().as_ref().map(|after| &after["rad"])
+                && update.rad().as_ref() == &150
+        })
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(()) }));
+    loc.expect_delete_localize()
+        .withf(move |_, code, entry| {
+            let after = entry.after().as_ref().map(|after| after["localize"].as_array().map(Vec::len));
+            *entry.action() == AuditAction::LocalizeDelete
+                && code.as_ref() == "en"
+                && after == Some(Some(1))
+        })
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(()) }));
+    loc.expect_delete()
+        .withf(move |_, entry| {
+            *entry.action() == AuditAction::LocationDelete
+                && entry.before().is_some()
+                && entry.after().is_none()
+        })
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(()) }));
+
+    let mut cache_loc = MockLocationETagCache::default();
+    cache_loc.expect_save()
+        .returning(|_| Box::pin(async { Ok(()) }));
+
+    let handler = Handler { loc, cache_loc };
+
+    let update = UpdateLocationDto {
+        actor,
+        id: Uuid::from(*location.id()),
+        latitude: 33.841405349477995f64,
+        longitude: 132.76661710012877f64,
+        radius: 150,
+        capacity: None,
+        localize: vec![("jp".to_string(), "愛媛県庁第一別館".to_string())],
+    };
+    let updated = handler.update_location_service().update(update).await?;
+    assert_eq!(updated.radius, 150);
+
+    let delete = DeleteLocationDto {
+        actor,
+        id: Uuid::from(*location.id()),
+        localize: Some("en".to_string()),
+    };
+    handler.delete_location_service().delete(delete).await?;
+
+    let delete = DeleteLocationDto {
+        actor,
+        id: Uuid::from(*location.id()),
+        localize: None,
+    };
+    handler.delete_location_service().delete(delete).await?;
+
+    Ok(())
+}
+
+pub struct Handler {
+    loc: MockLocationRepository,
+    cache_loc: MockLocationETagCache,
+}
+
+impl DependOnLocationRepository for Handler {
+    type LocationRepository = MockLocationRepository;
+    fn location_repository(&self) -> &Self::LocationRepository {
+        &self.loc
+    }
+}
+
+impl DependOnLocationETagCache for Handler {
+    type LocationETagCache = MockLocationETagCache;
+    fn location_e_tag_cache(&self) -> &Self::LocationETagCache {
+        &self.cache_loc
+    }
+}
+
+impl DependOnUpdateLocationService for Handler {
+    type UpdateLocationService = Self;
+    fn update_location_service(&self) -> &Self::UpdateLocationService {
+        self
+    }
+}
+
+impl DependOnDeleteLocationService for Handler {
+    type DeleteLocationService = Self;
+    fn delete_location_service(&self) -> &Self::DeleteLocationService {
+        self
+    }
+}
@@ -0,0 +1,148 @@
+use application::services::{DeleteRingService, DependOnDeleteRingService, DependOnHideRingService, DependOnRestoreRingService, HideRingService, RestoreRingService};
+use kernel::entities::audit::AuditAction;
+use kernel::entities::geology::Position;
+use kernel::entities::image::{Image, ImageBin};
+use kernel::entities::location::LocationId;
+use kernel::entities::ring::{CreatedAt, HiddenAt, HueColor, Index, Ring, RingId, UserId};
+use kernel::external::time::OffsetDateTime;
+use kernel::external::uuid::Uuid;
+use kernel::finder::{DependOnRingFinder, MockRingFinder};
+use kernel::repository::{DependOnImageRepository, DependOnRingRepository, MockImageRepository, MockRingRepository};
+use kernel::service::{DependOnImageExportExternalStorageService, MockImageExportExternalStorageService};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let ring = Ring::new(
+        RingId::default(),
+        Position::new(132.76661710012877f64, 33.841405349477995f64)?,
+        LocationId::default(),
+        UserId::default(),
+        Index::new(0)?,
+        HueColor::new(0),
+        CreatedAt::new(OffsetDateTime::now_utc()),
+        HiddenAt::default(),
+    );
+    let id = Uuid::from(ring.id().clone());
+    let actor = Uuid::new_v4();
+
+    let mut finder = MockRingFinder::default();
+    let found = ring.clone();
+    finder.expect_find_by_id()
+        .returning(move |_| {
+            let found = found.clone();
+            Box::pin(async move { Ok(Some(found)) })
+        });
+
+    let mut ring_repo = MockRingRepository::default();
+    ring_repo.expect_update()
+        .withf(move |update, entry| {
+            *entry.action() == AuditAction::RingHide
+                && Uuid::from(*entry.actor()) == actor
+                && entry.before().as_ref().map(|before| &before["hidden_at"]) != entry.after().as_ref().map(|after| &after["hidden_at"])
+                && update.hidden_at().is_hidden()
+        })
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(()) }));
+    ring_repo.expect_update()
+        .withf(move |update, entry| {
+            *entry.action() == AuditAction::RingRestore
+                && entry.after().as_ref().is_some_and(|after| after.get("hidden_at").is_none())
+                && !update.hidden_at().is_hidden()
+        })
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(()) }));
+    ring_repo.expect_delete()
+        .withf(move |delete, entry| {
+            *entry.action() == AuditAction::RingDelete
+                && Uuid::from(delete.clone()) == id
+                && entry.before().is_some()
+                && entry.after().is_none()
+        })
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(()) }));
+
+    let mut image = MockImageRepository::default();
+    let stored = Image::new(ring.id().clone(), ImageBin::new(vec![0u8; 8]), CreatedAt::new(OffsetDateTime::now_utc()));
+    image.expect_find_by_id()
+        .times(1)
+        .returning(move |_| {
+            let stored = stored.clone();
+            Box::pin(async move { Ok(Some(stored)) })
+        });
+
+    // The exported objects are public, so they have to be removed along with the ring.
+    let mut storage = MockImageExportExternalStorageService::default();
+    storage.expect_delete()
+        .withf(move |image| Uuid::from(image.id().clone()) == id)
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(()) }));
+
+    let handler = Handler { finder, ring_repo, image, storage };
+
+    let hidden = handler.hide_ring_service().hide(actor, id).await?;
+    assert!(hidden.hidden);
+
+    let restored = handler.restore_ring_service().restore(actor, id).await?;
+    assert!(!restored.hidden);
+
+    let deleted = handler.delete_ring_service().delete(actor, id).await?;
+    assert_eq!(deleted.id, id);
+
+    Ok(())
+}
+
+pub struct Handler {
+    finder: MockRingFinder,
+    ring_repo: MockRingRepository,
+    image: MockImageRepository,
+    storage: MockImageExportExternalStorageService,
+}
+
+impl DependOnRingFinder for Handler {
+    type RingFinder = MockRingFinder;
+    fn ring_finder(&self) -> &Self::RingFinder {
+        &self.finder
+    }
+}
+
+impl DependOnRingRepository for Handler {
+    type RingRepository = MockRingRepository;
+    fn ring_repository(&self) -> &Self::RingRepository {
+        &self.ring_repo
+    }
+}
+
+impl DependOnImageRepository for Handler {
+    type ImageRepository = MockImageRepository;
+    fn image_repository(&self) -> &Self::ImageRepository {
+        &self.image
+    }
+}
+
+impl DependOnImageExportExternalStorageService for Handler {
+    type ImageExportExternalStorageService = MockImageExportExternalStorageService;
+    fn image_export_external_storage_service(&self) -> &Self::ImageExportExternalStorageService {
+        &self.storage
+    }
+}
+
+impl DependOnHideRingService for Handler {
+    type HideRingService = Self;
+    fn hide_ring_service(&self) -> &Self::HideRingService {
+        self
+    }
+}
+
+impl DependOnRestoreRingService for Handler {
+    type RestoreRingService = Self;
+    fn restore_ring_service(&self) -> &Self::RestoreRingService {
+        self
+    }
+}
+
+impl DependOnDeleteRingService for Handler {
+    type DeleteRingService = Self;
+    fn delete_ring_service(&self) -> &Self::DeleteRingService {
+        self
+    }
+}
@@ -2,4 +2,8 @@
 fn test() {
     let try_build = trybuild::TestCases::new();
     try_build.pass("tests/ring.rs");
+    try_build.pass("tests/concurrency.rs");
+    try_build.pass("tests/expire.rs");
+    try_build.pass("tests/location.rs");
+    try_build.pass("tests/moderate.rs");
 }
\ No newline at end of file
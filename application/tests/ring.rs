@@ -1,32 +1,102 @@
+use std::sync::{Arc, Mutex};
+use application::error::ApplicationError;
 use application::services::{CreateRingService, DependOnCreateEmptyInstanceService, DependOnCreateInstanceService, DependOnCreateLocationService, DependOnCreateRingService, DependOnDeleteLocationService, DependOnUpdateInstanceService, DependOnUpdateLocationService};
 use application::transfer::CreateRingDto;
+use kernel::entities::geology::{Position, Radius};
+use kernel::entities::instance::{Allocation, Capacity, Instance};
+use kernel::entities::location::{Localize, Location, LocationId};
+use kernel::error::KernelError;
 use kernel::external::time::OffsetDateTime;
+use kernel::external::uuid::Uuid;
 use kernel::repository::{DependOnInstanceRepository, DependOnLocationRepository, MockInstanceRepository, MockLocationRepository};
 use kernel::volatiles::{DependOnLocationETagCache, MockLocationETagCache};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let location = Location::new(
+        LocationId::default(),
+        Position::new(132.76661710012877f64, 33.841405349477995f64)?,
+        Radius::new(100),
+        Capacity::new(12)?,
+        vec![Localize::new("jp", "愛媛県庁第一別館")?],
+    );
+
+    let mut loc = MockLocationRepository::default();
+    let found = location.clone();
+    loc.expect_find_by_id()
+        .returning(move |_| {
+            let found = found.clone();
+            Box::pin(async move { Ok(Some(found)) })
+        });
+
+    let mut ins = MockInstanceRepository::default();
+    let stored: Arc<Mutex<Option<Instance>>> = Arc::new(Mutex::new(None));
+    let state = Arc::clone(&stored);
+    ins.expect_find_unfinished()
+        .returning(move |_| {
+            let found = state.lock().unwrap().clone().filter(|i| !i.finished_at().is_finished());
+            Box::pin(async move { Ok(found) })
+        });
+    let state = Arc::clone(&stored);
+    ins.expect_find_by_id()
+        .returning(move |_| {
+            let found = state.lock().unwrap().clone();
+            Box::pin(async move { Ok(found) })
+        });
+    let state = Arc::clone(&stored);
+    ins.expect_create()
+        .returning(move |i| {
+            *state.lock().unwrap() = Some(i.clone());
+            Box::pin(async move { Ok(()) })
+        });
+    let state = Arc::clone(&stored);
+    ins.expect_update()
+        .returning(move |i| {
+            *state.lock().unwrap() = Some(i.clone());
+            Box::pin(async move { Ok(()) })
+        });
+
     let handler = Handler {
-        loc: MockLocationRepository::default(),
-        ins: MockInstanceRepository::default(),
+        loc,
+        ins,
         cache_loc: MockLocationETagCache::default(),
     };
 
-    for index in 0..=69 {
+    let far = CreateRingDto {
+        location: Uuid::from(*location.id()),
+        longitude: 132.7766f64,
+        latitude: 33.8414f64,
+        indexed: Some(0),
+        allocation: Allocation::default(),
+        hue: 0,
+        user: Uuid::new_v4(),
+        created_at: OffsetDateTime::now_utc(),
+    };
+    let res = handler.create_ring_service().create(far).await;
+    assert!(matches!(res, Err(ApplicationError::Kernel(ref kind)) if matches!(kind.error, KernelError::OutOfRange { .. })));
+
+    for index in 0..12 {
         let post = CreateRingDto {
-            location: Default::default(),
-            longitude: index as f64,
-            latitude: index as f64,
-            indexed: index,
+            location: Uuid::from(*location.id()),
+            longitude: 132.7666 + index as f64 * 0.000001,
+            latitude: 33.8414,
+            // odd slots are left to the server, and the next free one is always that slot.
+            indexed: (index % 2 == 0).then_some(index),
+            allocation: Allocation::Next,
             hue: index,
-            user: Default::default(),
+            user: Uuid::new_v4(),
             created_at: OffsetDateTime::now_utc(),
         };
-        handler.create_ring_service()
+        let ring = handler.create_ring_service()
             .create(post)
             .await?;
+        assert_eq!(ring.indexed, index);
     }
 
+    let finished = stored.lock().unwrap().clone().expect("instance should be stored.");
+    assert_eq!(finished.rings().len(), 12);
+    assert!(finished.finished_at().is_finished());
+
     Ok(())
 }
 
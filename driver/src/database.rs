@@ -1,3 +1,5 @@
+mod admin_key;
+mod audit;
 mod images;
 mod instance;
 mod location;
@@ -5,6 +7,8 @@ mod rings;
 
 pub const BIND_LIMIT: usize = 65535;
 
+pub use self::admin_key::*;
+pub use self::audit::*;
 pub use self::images::*;
 pub use self::instance::*;
 pub use self::location::*;
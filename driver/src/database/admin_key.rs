@@ -0,0 +1,236 @@
+use crate::error::DriverError;
+use async_trait::async_trait;
+use kernel::entities::token::{AdminKey, AdminKeyId, KeyDigest, KeyName, Scope};
+use kernel::error::KernelError;
+use kernel::external::time::OffsetDateTime;
+use kernel::external::uuid::Uuid;
+use kernel::repository::AdminKeyRepository;
+use sqlx::{PgConnection, Pool, Postgres};
+
+#[derive(Clone)]
+pub struct AdminKeyDataBase {
+    pool: Pool<Postgres>,
+}
+
+impl AdminKeyDataBase {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AdminKeyRepository for AdminKeyDataBase {
+    async fn create(&self, create: &AdminKey) -> Result<(), KernelError> {
+        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
+        AdminKeyDataBaseInternal::create(create, &mut con).await?;
+        Ok(())
+    }
+
+    async fn revoke(&self, id: &AdminKeyId, at: &OffsetDateTime) -> Result<bool, KernelError> {
+        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
+        let revoked = AdminKeyDataBaseInternal::revoke(id, at, &mut con).await?;
+        Ok(revoked)
+    }
+
+    async fn touch(&self, id: &AdminKeyId, at: &OffsetDateTime) -> Result<(), KernelError> {
+        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
+        AdminKeyDataBaseInternal::touch(id, at, &mut con).await?;
+        Ok(())
+    }
+
+    async fn find_all(&self) -> Result<Vec<AdminKey>, KernelError> {
+        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
+        let keys = AdminKeyDataBaseInternal::find_all(&mut con).await?;
+        Ok(keys)
+    }
+
+    async fn find_by_digest(&self, digest: &KeyDigest) -> Result<Option<AdminKey>, KernelError> {
+        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
+        let key = AdminKeyDataBaseInternal::find_by_digest(digest, &mut con).await?;
+        Ok(key)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub(in crate::database) struct AdminKeyRow {
+    pub id: Uuid,
+    pub name: String,
+    pub digest: String,
+    pub scopes: Vec<String>,
+    pub created_at: OffsetDateTime,
+    pub expires_at: Option<OffsetDateTime>,
+    pub last_used_at: Option<OffsetDateTime>,
+    pub revoked_at: Option<OffsetDateTime>,
+}
+
+impl TryFrom<AdminKeyRow> for AdminKey {
+    type Error = DriverError;
+    fn try_from(value: AdminKeyRow) -> Result<Self, Self::Error> {
+        let scopes = value
+            .scopes
+            .iter()
+            .map(|scope| scope.parse::<Scope>())
+            .collect::<Result<_, _>>()?;
+        Ok(AdminKey::new(
+            AdminKeyId::new(value.id),
+            KeyName::new(value.name)?,
+            KeyDigest::unchecked_new(value.digest),
+            scopes,
+            value.created_at,
+            value.expires_at,
+            value.last_used_at,
+            value.revoked_at,
+        ))
+    }
+}
+
+pub(in crate::database) struct AdminKeyDataBaseInternal;
+
+impl AdminKeyDataBaseInternal {
+    pub(in crate::database) async fn create(
+        ctx: &AdminKey,
+        con: &mut PgConnection,
+    ) -> Result<(), DriverError> {
+        let scopes = ctx.scopes().iter().map(Scope::to_string).collect::<Vec<_>>();
+        // language=SQL
+        sqlx::query(
+            r#"
+            INSERT INTO admin_keys(
+              id, name, digest, scopes, created_at, expires_at, last_used_at, revoked_at
+            ) VALUES (
+              $1, $2, $3, $4, $5, $6, $7, $8
+            )
+        "#,
+        )
+        .bind(ctx.id().as_ref())
+        .bind(ctx.name().as_ref())
+        .bind(ctx.digest().as_ref())
+        .bind(scopes)
+        .bind(ctx.created_at())
+        .bind(ctx.expires_at())
+        .bind(ctx.last_used_at())
+        .bind(ctx.revoked_at())
+        .execute(&mut *con)
+        .await
+        .map_err(|e| DriverError::conflict(e, "admin_key"))?;
+        Ok(())
+    }
+
+    pub(in crate::database) async fn revoke(
+        id: &AdminKeyId,
+        at: &OffsetDateTime,
+        con: &mut PgConnection,
+    ) -> Result<bool, DriverError> {
+        // language=SQL
+        let res = sqlx::query(
+            r#"
+            UPDATE admin_keys SET revoked_at = $2 WHERE id = $1 AND revoked_at IS NULL
+        "#,
+        )
+        .bind(id.as_ref())
+        .bind(at)
+        .execute(&mut *con)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    pub(in crate::database) async fn touch(
+        id: &AdminKeyId,
+        at: &OffsetDateTime,
+        con: &mut PgConnection,
+    ) -> Result<(), DriverError> {
+        // language=SQL
+        sqlx::query(
+            r#"
+            UPDATE admin_keys SET last_used_at = $2 WHERE id = $1
+        "#,
+        )
+        .bind(id.as_ref())
+        .bind(at)
+        .execute(&mut *con)
+        .await?;
+        Ok(())
+    }
+
+    pub(in crate::database) async fn find_all(
+        con: &mut PgConnection,
+    ) -> Result<Vec<AdminKey>, DriverError> {
+        // language=SQL
+        let rows = sqlx::query_as::<_, AdminKeyRow>(
+            r#"
+            SELECT id, name, digest, scopes, created_at, expires_at, last_used_at, revoked_at
+            FROM admin_keys
+            ORDER BY created_at
+        "#,
+        )
+        .fetch_all(&mut *con)
+        .await?;
+        rows.into_iter().map(AdminKey::try_from).collect()
+    }
+
+    pub(in crate::database) async fn find_by_digest(
+        digest: &KeyDigest,
+        con: &mut PgConnection,
+    ) -> Result<Option<AdminKey>, DriverError> {
+        // language=SQL
+        let row = sqlx::query_as::<_, AdminKeyRow>(
+            r#"
+            SELECT id, name, digest, scopes, created_at, expires_at, last_used_at, revoked_at
+            FROM admin_keys
+            WHERE digest = $1
+        "#,
+        )
+        .bind(digest.as_ref())
+        .fetch_optional(&mut *con)
+        .await?;
+        row.map(AdminKey::try_from).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::admin_key::AdminKeyDataBaseInternal;
+    use kernel::entities::token::{AdminKey, KeyDigest, KeyName, Scope};
+    use kernel::external::time::OffsetDateTime;
+    use sqlx::postgres::PgPoolOptions;
+    use sqlx::{Pool, Postgres};
+    use std::collections::BTreeSet;
+    use std::time::Duration;
+
+    async fn test_pool() -> anyhow::Result<Pool<Postgres>> {
+        dotenvy::dotenv().ok();
+
+        let url = dotenvy::var("PG_DATABASE_URL")
+            .expect("`PG_DATABASE_URL` is not set. This is a required environment variable.");
+        let pool = PgPoolOptions::new()
+            .max_connections(4)
+            .idle_timeout(Duration::new(5, 0))
+            .connect(&url)
+            .await?;
+
+        Ok(pool)
+    }
+
+    #[ignore = "It depends on Postgres and does not work as is."]
+    #[tokio::test]
+    async fn pg_issue_and_revoke() -> anyhow::Result<()> {
+        let pool = test_pool().await?;
+        let mut transaction = pool.begin().await?;
+
+        let (key, token) = AdminKey::issue(KeyName::new("test")?, BTreeSet::from([Scope::All]), None)?;
+        AdminKeyDataBaseInternal::create(&key, &mut transaction).await?;
+
+        let found = AdminKeyDataBaseInternal::find_by_digest(&KeyDigest::of(&token), &mut transaction).await?;
+        assert_eq!(found.as_ref().map(|found| *found.id()), Some(*key.id()));
+
+        let now = OffsetDateTime::now_utc();
+        assert!(AdminKeyDataBaseInternal::revoke(key.id(), &now, &mut transaction).await?);
+        assert!(!AdminKeyDataBaseInternal::revoke(key.id(), &now, &mut transaction).await?);
+
+        let found = AdminKeyDataBaseInternal::find_by_digest(key.digest(), &mut transaction).await?;
+        assert!(found.is_some_and(|found| !found.is_active(now)));
+
+        transaction.rollback().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+use crate::error::DriverError;
+use async_trait::async_trait;
+use kernel::entities::audit::{AuditAction, AuditEntry, AuditFilter};
+use kernel::entities::page::{Cursor, Limit, Page};
+use kernel::entities::token::AdminKeyId;
+use kernel::error::KernelError;
+use kernel::external::time::OffsetDateTime;
+use kernel::external::uuid::Uuid;
+use kernel::repository::AuditLog;
+use serde_json::Value;
+use sqlx::types::Json;
+use sqlx::{PgConnection, Pool, Postgres, QueryBuilder};
+
+#[derive(Clone)]
+pub struct AuditLogDataBase {
+    pool: Pool<Postgres>,
+}
+
+impl AuditLogDataBase {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuditLog for AuditLogDataBase {
+    async fn record(&self, entry: &AuditEntry) -> Result<(), KernelError> {
+        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
+        AuditLogDataBaseInternal::record(entry, &mut con).await?;
+        Ok(())
+    }
+
+    async fn find_page(&self, filter: &AuditFilter, after: Option<&Cursor>, limit: &Limit) -> Result<Page<AuditEntry>, KernelError> {
+        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
+        let page = AuditLogDataBaseInternal::find_page(filter, after, limit, &mut con).await?;
+        Ok(page)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub(in crate::database) struct AuditRow {
+    pub id: Uuid,
+    pub actor: Uuid,
+    pub action: String,
+    pub target: Uuid,
+    pub before: Option<Json<Value>>,
+    pub after: Option<Json<Value>>,
+    pub recorded_at: OffsetDateTime,
+}
+
+impl TryFrom<AuditRow> for AuditEntry {
+    type Error = DriverError;
+    fn try_from(value: AuditRow) -> Result<Self, Self::Error> {
+        Ok(AuditEntry::new(
+            value.id,
+            AdminKeyId::new(value.actor),
+            value.action.parse::<AuditAction>()?,
+            value.target,
+            value.before.map(|before| before.0),
+            value.after.map(|after| after.0),
+            value.recorded_at,
+        ))
+    }
+}
+
+pub(in crate::database) struct AuditLogDataBaseInternal;
+
+impl AuditLogDataBaseInternal {
+    pub(in crate::database) async fn record(
+        ctx: &AuditEntry,
+        con: &mut PgConnection,
+    ) -> Result<(), DriverError> {
+        // language=SQL
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log(
+              id, actor, action, target, before, after, recorded_at
+            ) VALUES (
+              $1, $2, $3, $4, $5, $6, $7
+            )
+        "#,
+        )
+        .bind(ctx.id())
+        .bind(ctx.actor().as_ref())
+        .bind(ctx.action().as_str())
+        .bind(ctx.target())
+        .bind(ctx.before().as_ref().map(Json))
+        .bind(ctx.after().as_ref().map(Json))
+        .bind(ctx.recorded_at())
+        .execute(&mut *con)
+        .await?;
+        Ok(())
+    }
+
+    #[rustfmt::skip]
+    pub(in crate::database) async fn find_page(filter: &AuditFilter, after: Option<&Cursor>, limit: &Limit, con: &mut PgConnection) -> Result<Page<AuditEntry>, DriverError> {
+        let mut query: QueryBuilder<Postgres> = QueryBuilder::new(r#"
+            SELECT id, actor, action, target, before, after, recorded_at FROM audit_log WHERE TRUE
+        "#);
+
+        if let Some(actor) = filter.actor() {
+            query.push(" AND actor = ").push_bind(actor.as_ref());
+        }
+        if let Some(action) = filter.action() {
+            query.push(" AND action = ").push_bind(action.as_str());
+        }
+        if let Some(target) = filter.target() {
+            query.push(" AND target = ").push_bind(target);
+        }
+        if let Some(since) = filter.recorded().since() {
+            query.push(" AND recorded_at >= ").push_bind(since);
+        }
+        if let Some(until) = filter.recorded().until() {
+            query.push(" AND recorded_at < ").push_bind(until);
+        }
+        if let Some(after) = after {
+            query.push(" AND (recorded_at, id) < (")
+                .push_bind(after.at())
+                .push(", ")
+                .push_bind(after.id())
+                .push(")");
+        }
+
+        // one more than the limit, to tell if there is a following page.
+        query.push(" ORDER BY recorded_at DESC, id DESC LIMIT ")
+            .push_bind(i64::from(*limit) + 1);
+
+        let entries = query.build_query_as::<AuditRow>()
+            .fetch_all(&mut *con)
+            .await?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<AuditEntry>, DriverError>>()?;
+
+        Ok(Page::from_surplus(entries, limit, |entry| {
+            Cursor::new(*entry.recorded_at(), *entry.id())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::admin_key::AdminKeyDataBaseInternal;
+    use crate::database::audit::AuditLogDataBaseInternal;
+    use kernel::entities::audit::{AuditAction, AuditEntry, AuditFilter};
+    use kernel::entities::instance::Period;
+    use kernel::entities::page::Limit;
+    use kernel::entities::token::{AdminKey, KeyName, Scope};
+    use kernel::external::uuid::Uuid;
+    use serde_json::json;
+    use sqlx::postgres::PgPoolOptions;
+    use sqlx::{Pool, Postgres};
+    use std::collections::BTreeSet;
+    use std::time::Duration;
+
+    async fn test_pool() -> anyhow::Result<Pool<Postgres>> {
+        dotenvy::dotenv().ok();
+
+        let url = dotenvy::var("PG_DATABASE_URL")
+            .expect("`PG_DATABASE_URL` is not set. This is a required environment variable.");
+        let pool = PgPoolOptions::new()
+            .max_connections(4)
+            .idle_timeout(Duration::new(5, 0))
+            .connect(&url)
+            .await?;
+
+        Ok(pool)
+    }
+
+    #[ignore = "It depends on Postgres and does not work as is."]
+    #[tokio::test]
+    async fn pg_record_and_find() -> anyhow::Result<()> {
+        let pool = test_pool().await?;
+        let mut transaction = pool.begin().await?;
+
+        let (key, _) = AdminKey::issue(KeyName::new("test")?, BTreeSet::from([Scope::All]), None)?;
+        AdminKeyDataBaseInternal::create(&key, &mut transaction).await?;
+
+        let target = Uuid::new_v4();
+        let entry = AuditEntry::record(*key.id(), AuditAction::LocationCreate, target, None, Some(&json!({ "radius": 100 })))?;
+        AuditLogDataBaseInternal::record(&entry, &mut transaction).await?;
+        let entry = AuditEntry::record(*key.id(), AuditAction::LocationDelete, target, Some(&json!({ "radius": 100 })), None)?;
+        AuditLogDataBaseInternal::record(&entry, &mut transaction).await?;
+
+        let filter = AuditFilter::new(Some(*key.id()), None, Some(target), Period::default());
+        let page = AuditLogDataBaseInternal::find_page(&filter, None, &Limit::new(1)?, &mut transaction).await?;
+        assert_eq!(page.items().first().map(|entry| *entry.action()), Some(AuditAction::LocationDelete));
+        assert!(page.next().is_some());
+
+        let page = AuditLogDataBaseInternal::find_page(&filter, page.next(), &Limit::new(1)?, &mut transaction).await?;
+        assert_eq!(page.items().first().and_then(|entry| entry.after().clone()), Some(json!({ "radius": 100 })));
+        assert!(page.next().is_none());
+
+        transaction.rollback().await?;
+        Ok(())
+    }
+}
@@ -4,15 +4,15 @@ use async_trait::async_trait;
 use geo_types::Geometry;
 use geozero::wkb::Decode;
 use kernel::entities::geology::Position;
-use kernel::entities::instance::{FinishedAt, Instance, InstanceId, RingSet, StartedAt};
+use kernel::entities::instance::{Capacity, FinishedAt, Instance, InstanceFilter, InstanceId, RingSet, StartedAt, Version};
 use kernel::entities::location::LocationId;
-use kernel::entities::ring::{CreatedAt, HueColor, Index, Ring, RingId, UserId};
+use kernel::entities::page::{Cursor, Limit, Page};
+use kernel::entities::ring::{CreatedAt, HiddenAt, HueColor, Index, Ring, RingId, UserId};
 use kernel::error::KernelError;
 use kernel::external::time::OffsetDateTime;
 use kernel::external::uuid::Uuid;
 use kernel::repository::InstanceRepository;
 use sqlx::{PgConnection, Pool, Postgres, QueryBuilder};
-use std::collections::BTreeSet;
 
 #[derive(Clone)]
 pub struct InstanceDataBase {
@@ -33,8 +33,9 @@ impl InstanceRepository for InstanceDataBase {
         Ok(())
     }
     async fn update(&self, update: &Instance) -> Result<(), KernelError> {
-        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
-        InternalInstanceDataBase::update(update, &mut con).await?;
+        let mut transaction = self.pool.begin().await.map_err(DriverError::from)?;
+        InternalInstanceDataBase::update(update, &mut transaction).await?;
+        transaction.commit().await.map_err(DriverError::from)?;
         Ok(())
     }
     async fn delete(&self, delete: &InstanceId) -> Result<(), KernelError> {
@@ -43,10 +44,10 @@ impl InstanceRepository for InstanceDataBase {
         Ok(())
     }
 
-    async fn find_all(&self) -> Result<BTreeSet<Instance>, KernelError> {
+    async fn find_page(&self, filter: &InstanceFilter, after: Option<&Cursor>, limit: &Limit) -> Result<Page<Instance>, KernelError> {
         let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
-        let all = InternalInstanceDataBase::find_all(&mut con).await?;
-        Ok(all)
+        let page = InternalInstanceDataBase::find_page(filter, after, limit, &mut con).await?;
+        Ok(page)
     }
 
     async fn find_by_id(&self, id: &InstanceId) -> Result<Option<Instance>, KernelError> {
@@ -55,9 +56,15 @@ impl InstanceRepository for InstanceDataBase {
         Ok(found)
     }
 
-    async fn find_unfinished(&self) -> Result<Option<Instance>, KernelError> {
+    async fn find_unfinished(&self, location: &LocationId) -> Result<Option<Instance>, KernelError> {
+        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
+        let found = InternalInstanceDataBase::find_unfinished(location, &mut con).await?;
+        Ok(found)
+    }
+
+    async fn find_all_unfinished(&self) -> Result<Vec<Instance>, KernelError> {
         let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
-        let found = InternalInstanceDataBase::find_unfinished(&mut con).await?;
+        let found = InternalInstanceDataBase::find_all_unfinished(&mut con).await?;
         Ok(found)
     }
 }
@@ -65,8 +72,11 @@ impl InstanceRepository for InstanceDataBase {
 #[derive(sqlx::FromRow)]
 pub(in crate::database) struct InstanceRow {
     id: Uuid,
+    location: Uuid,
+    capacity: i32,
     started_at: OffsetDateTime,
     finished_at: Option<OffsetDateTime>,
+    version: i64,
 }
 
 impl TryFrom<InstanceRow> for Instance {
@@ -74,9 +84,11 @@ impl TryFrom<InstanceRow> for Instance {
     fn try_from(value: InstanceRow) -> Result<Self, Self::Error> {
         Ok(Self::new(
             InstanceId::new(value.id),
-            RingSet::default(),
+            LocationId::new(value.location),
+            RingSet::new(Capacity::new(value.capacity)?, Vec::new())?,
             StartedAt::new(value.started_at),
             FinishedAt::new::<OffsetDateTime>(value.finished_at),
+            Version::new(value.version),
         ))
     }
 }
@@ -85,11 +97,12 @@ impl TryFrom<InstanceRow> for Instance {
 pub(in crate::database) struct RingRow {
     id: Uuid,
     pos_in: Decode<Geometry>,
-    location: Uuid,
+    pub(in crate::database) location: Uuid,
     hue: i32,
     user_id: Uuid,
     index: i32,
     created_at: OffsetDateTime,
+    hidden_at: Option<OffsetDateTime>,
 }
 
 impl TryFrom<RingRow> for Ring {
@@ -108,6 +121,7 @@ impl TryFrom<RingRow> for Ring {
             Index::new(value.index)?,
             HueColor::new(value.hue),
             CreatedAt::new(value.created_at),
+            HiddenAt::new::<OffsetDateTime>(value.hidden_at),
         ))
     }
 }
@@ -120,34 +134,47 @@ impl InternalInstanceDataBase {
         // language=SQL
         sqlx::query(r#"
             INSERT INTO instances(
-              id, started_at, finished_at
+              id, location, capacity, started_at, finished_at, version
             ) VALUES (
-              $1, $2, $3
+              $1, $2, $3, $4, $5, $6
             )
         "#)
             .bind(create.id().as_ref())
+            .bind(create.location().as_ref())
+            .bind(create.rings().capacity().as_ref())
             .bind(create.started_at().as_ref())
             .bind(create.finished_at().as_ref())
+            .bind(create.version().as_ref())
             .execute(&mut *con)
-            .await?;
+            .await
+            .map_err(|e| DriverError::conflict(e, "instance"))?;
         Ok(())
     }
 
     #[rustfmt::skip]
     pub(in crate::database) async fn update(update: &Instance, con: &mut PgConnection) -> Result<(), DriverError> {
         // language=SQL
-        sqlx::query(r#"
+        let updated = sqlx::query(r#"
             UPDATE instances
               SET
-                finished_at = $1
+                finished_at = $1,
+                version = version + 1
             WHERE
-              id = $2
+              id = $2 AND version = $3
         "#)
             .bind(update.finished_at().as_ref())
             .bind(update.id().as_ref())
+            .bind(update.version().as_ref())
             .execute(&mut *con)
             .await?;
 
+        if updated.rows_affected() == 0 {
+            return Err(DriverError::Conflict { entity: "instance" });
+        }
+
+        if update.rings().is_empty() {
+            return Ok(());
+        }
 
         let mut query: QueryBuilder<Postgres> = QueryBuilder::new(r#"
             INSERT INTO rings (
@@ -171,7 +198,10 @@ impl InternalInstanceDataBase {
 
         query.push("ON CONFLICT(id) DO NOTHING");
 
-        query.build().execute(&mut *con).await?;
+        query.build()
+            .execute(&mut *con)
+            .await
+            .map_err(|e| DriverError::conflict(e, "ring"))?;
 
         Ok(())
     }
@@ -189,25 +219,65 @@ impl InternalInstanceDataBase {
     }
 
     #[rustfmt::skip]
-    pub(in crate::database) async fn find_all(con: &mut PgConnection) -> Result<BTreeSet<Instance>, DriverError> {
-        // language=SQL
-        let all = sqlx::query_as::<_, InstanceRow>(r#"
-            SELECT id, started_at, finished_at FROM instances
-        "#)
+    pub(in crate::database) async fn find_page(filter: &InstanceFilter, after: Option<&Cursor>, limit: &Limit, con: &mut PgConnection) -> Result<Page<Instance>, DriverError> {
+        let mut query: QueryBuilder<Postgres> = QueryBuilder::new(r#"
+            SELECT id, location, capacity, started_at, finished_at, version FROM instances WHERE TRUE
+        "#);
+
+        if let Some(location) = filter.location() {
+            query.push(" AND location = ").push_bind(location.as_ref());
+        }
+        if let Some(user) = filter.user() {
+            query.push(" AND EXISTS (SELECT 1 FROM rings WHERE rings.instance = instances.id AND rings.user_id = ")
+                .push_bind(user.as_ref())
+                .push(")");
+        }
+        if let Some(since) = filter.started().since() {
+            query.push(" AND started_at >= ").push_bind(since);
+        }
+        if let Some(until) = filter.started().until() {
+            query.push(" AND started_at < ").push_bind(until);
+        }
+        if let Some(since) = filter.finished().since() {
+            query.push(" AND finished_at >= ").push_bind(since);
+        }
+        if let Some(until) = filter.finished().until() {
+            query.push(" AND finished_at < ").push_bind(until);
+        }
+        match filter.is_finished() {
+            Some(true) => { query.push(" AND finished_at IS NOT NULL"); },
+            Some(false) => { query.push(" AND finished_at IS NULL"); },
+            None => {}
+        }
+        if let Some(after) = after {
+            query.push(" AND (started_at, id) < (")
+                .push_bind(after.at())
+                .push(", ")
+                .push_bind(after.id())
+                .push(")");
+        }
+
+        // one more than the limit, to tell if there is a following page.
+        query.push(" ORDER BY started_at DESC, id DESC LIMIT ")
+            .push_bind(i64::from(*limit) + 1);
+
+        let instances = query.build_query_as::<InstanceRow>()
             .fetch_all(&mut *con)
             .await?
             .into_iter()
             .map(TryInto::try_into)
-            .collect::<Result<BTreeSet<Instance>, KernelError>>()?;
+            .collect::<Result<Vec<Instance>, KernelError>>()?;
 
-        Ok(all)
+        Ok(Page::from_surplus(instances, limit, |instance| {
+            Cursor::new(*instance.started_at().as_ref(), instance.id().clone())
+        }))
     }
 
     #[rustfmt::skip]
     pub(in crate::database) async fn find_by_id(id: &InstanceId, con: &mut PgConnection) -> Result<Option<Instance>, DriverError> {
         // language=SQL
         let i_row = sqlx::query_as::<_, InstanceRow>(r#"
-            SELECT id, started_at, finished_at FROM instances WHERE id = $1
+            SELECT id, location, capacity, started_at, finished_at, version FROM instances WHERE id = $1
         "#)
             .bind(id.as_ref())
             .fetch_optional(&mut *con)
@@ -217,27 +287,18 @@ impl InternalInstanceDataBase {
 
         // language=SQL
         let r_row = sqlx::query_as::<_, RingRow>(r#"
-            SELECT id, pos_in::GEOMETRY, location, hue, user_id, index, created_at FROM rings WHERE instance = $1
+            SELECT id, pos_in::GEOMETRY, location, hue, user_id, index, created_at, hidden_at FROM rings WHERE instance = $1
         "#)
             .bind(id.as_ref())
             .fetch_all(&mut *con)
             .await?
             .into_iter()
-            .map(|row| -> Result<Ring, KernelError> {
-                let id = RingId::new(row.id);
-                let pos_in = Position::try_from(row.pos_in.geometry.unwrap())?;
-                let location = LocationId::new(row.location);
-                let user = UserId::new(row.user_id);
-                let index = Index::new(row.index)?;
-                let color = HueColor::new(row.hue);
-                let created_at = CreatedAt::new(row.created_at);
-                Ok(Ring::new(id, pos_in, location, user, index, color, created_at))
-            })
-            .collect::<Result<Vec<_>, KernelError>>()?;
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<Ring>, DriverError>>()?;
 
         let found = i_row.map(|instance: Instance| instance.into_destruct())
             .map(|mut des| -> Result<Instance, KernelError> {
-                let rings = RingSet::new(r_row)?;
+                let rings = RingSet::new(*des.rings.capacity(), r_row)?;
                 des.rings = rings;
                 Ok(des.freeze())
             })
@@ -247,18 +308,19 @@ impl InternalInstanceDataBase {
     }
 
     #[rustfmt::skip]
-    pub(in crate::database) async fn find_unfinished(con: &mut PgConnection) -> Result<Option<Instance>, DriverError> {
+    pub(in crate::database) async fn find_unfinished(location: &LocationId, con: &mut PgConnection) -> Result<Option<Instance>, DriverError> {
         // language=SQL
         let instance = sqlx::query_as::<_, InstanceRow>(r#"
-            SELECT id, started_at, finished_at FROM instances WHERE instances.finished_at IS NULL
+            SELECT id, location, capacity, started_at, finished_at, version FROM instances WHERE instances.location = $1 AND instances.finished_at IS NULL
         "#)
+            .bind(location.as_ref())
             .fetch_optional(&mut *con)
             .await?;
 
         let rings = if let Some(instance) = &instance {
             // language=SQL
             sqlx::query_as::<_, RingRow>(r#"
-                SELECT id, instance, pos_in::GEOMETRY, location, hue, user_id, index, created_at FROM rings WHERE instance = $1
+                SELECT id, instance, pos_in::GEOMETRY, location, hue, user_id, index, created_at, hidden_at FROM rings WHERE instance = $1
             "#)
                 .bind(instance.id)
                 .fetch_all(&mut *con)
@@ -274,11 +336,44 @@ impl InternalInstanceDataBase {
         let found = instance.map(TryInto::try_into)
             .transpose()?
             .map(|ins: Instance| ins.try_reconstruct(|dest| -> Result<(), KernelError> {
-                dest.rings = RingSet::new(rings)?;
+                dest.rings = RingSet::new(*dest.rings.capacity(), rings)?;
                 Ok(())
             }))
             .transpose()?;
 
         Ok(found)
     }
+
+    #[rustfmt::skip]
+    pub(in crate::database) async fn find_all_unfinished(con: &mut PgConnection) -> Result<Vec<Instance>, DriverError> {
+        // language=SQL
+        let instances = sqlx::query_as::<_, InstanceRow>(r#"
+            SELECT id, location, capacity, started_at, finished_at, version FROM instances WHERE instances.finished_at IS NULL
+        "#)
+            .fetch_all(&mut *con)
+            .await?;
+
+        let mut found = Vec::with_capacity(instances.len());
+        for instance in instances {
+            // language=SQL
+            let rings = sqlx::query_as::<_, RingRow>(r#"
+                SELECT id, instance, pos_in::GEOMETRY, location, hue, user_id, index, created_at, hidden_at FROM rings WHERE instance = $1
+            "#)
+                .bind(instance.id)
+                .fetch_all(&mut *con)
+                .await?
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<Ring>, DriverError>>()?;
+
+            let instance = Instance::try_from(instance)?
+                .try_reconstruct(|dest| -> Result<(), KernelError> {
+                    dest.rings = RingSet::new(*dest.rings.capacity(), rings)?;
+                    Ok(())
+                })?;
+            found.push(instance);
+        }
+
+        Ok(found)
+    }
 }
@@ -1,10 +1,14 @@
+use crate::database::audit::AuditLogDataBaseInternal;
 use crate::database::BIND_LIMIT;
 use crate::error::DriverError;
 use async_trait::async_trait;
 use geo_types::Geometry;
 use geozero::wkb::Decode;
-use kernel::entities::geology::{Position, Radius};
+use kernel::entities::audit::AuditEntry;
+use kernel::entities::geology::{Distance, Position, Radius};
+use kernel::entities::instance::Capacity;
 use kernel::entities::location::{Localize, LocalizeId, Location, LocationId};
+use kernel::entities::page::Limit;
 use kernel::error::KernelError;
 use kernel::external::uuid::Uuid;
 use kernel::repository::LocationRepository;
@@ -23,21 +27,27 @@ impl LocationDataBase {
 
 #[async_trait]
 impl LocationRepository for LocationDataBase {
-    async fn create(&self, create: &Location) -> Result<(), KernelError> {
-        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
-        LocationDataBaseInternal::create(create, &mut con).await?;
+    async fn create(&self, create: &Location, audit: &AuditEntry) -> Result<(), KernelError> {
+        let mut transaction = self.pool.begin().await.map_err(DriverError::from)?;
+        LocationDataBaseInternal::create(create, &mut transaction).await?;
+        AuditLogDataBaseInternal::record(audit, &mut transaction).await?;
+        transaction.commit().await.map_err(DriverError::from)?;
         Ok(())
     }
 
-    async fn update(&self, update: &Location) -> Result<(), KernelError> {
-        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
-        LocationDataBaseInternal::update(update, &mut con).await?;
+    async fn update(&self, update: &Location, audit: &AuditEntry) -> Result<(), KernelError> {
+        let mut transaction = self.pool.begin().await.map_err(DriverError::from)?;
+        LocationDataBaseInternal::update(update, &mut transaction).await?;
+        AuditLogDataBaseInternal::record(audit, &mut transaction).await?;
+        transaction.commit().await.map_err(DriverError::from)?;
         Ok(())
     }
 
-    async fn delete(&self, delete: &LocationId) -> Result<(), KernelError> {
-        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
-        LocationDataBaseInternal::delete(delete, &mut con).await?;
+    async fn delete(&self, delete: &LocationId, audit: &AuditEntry) -> Result<(), KernelError> {
+        let mut transaction = self.pool.begin().await.map_err(DriverError::from)?;
+        LocationDataBaseInternal::delete(delete, &mut transaction).await?;
+        AuditLogDataBaseInternal::record(audit, &mut transaction).await?;
+        transaction.commit().await.map_err(DriverError::from)?;
         Ok(())
     }
 
@@ -45,9 +55,12 @@ impl LocationRepository for LocationDataBase {
         &self,
         delete: &LocationId,
         code: &LocalizeId,
+        audit: &AuditEntry,
     ) -> Result<(), KernelError> {
-        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
-        LocationDataBaseInternal::delete_localize(delete, code, &mut con).await?;
+        let mut transaction = self.pool.begin().await.map_err(DriverError::from)?;
+        LocationDataBaseInternal::delete_localize(delete, code, &mut transaction).await?;
+        AuditLogDataBaseInternal::record(audit, &mut transaction).await?;
+        transaction.commit().await.map_err(DriverError::from)?;
         Ok(())
     }
 
@@ -62,6 +75,17 @@ impl LocationRepository for LocationDataBase {
         let loc = LocationDataBaseInternal::find_by_id(id, &mut con).await?;
         Ok(loc)
     }
+
+    async fn find_nearby(
+        &self,
+        center: &Position,
+        within: &Distance,
+        limit: &Limit,
+    ) -> Result<Vec<(Location, Distance)>, KernelError> {
+        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
+        let loc = LocationDataBaseInternal::find_nearby(center, within, limit, &mut con).await?;
+        Ok(loc)
+    }
 }
 
 #[allow(unused)]
@@ -70,6 +94,7 @@ pub(in crate::database) struct LocationMarkRow {
     pub id: Uuid,
     pub location: Decode<Geometry>,
     pub radius: i32,
+    pub capacity: i32,
 }
 
 #[allow(unused)]
@@ -80,6 +105,13 @@ pub(in crate::database) struct LocationLocalizedRow {
     pub name: String,
 }
 
+#[derive(sqlx::FromRow)]
+pub(in crate::database) struct NearbyLocationRow {
+    #[sqlx(flatten)]
+    pub mark: LocationMarkRow,
+    pub distance: f64,
+}
+
 pub(in crate::database) struct LocationDataBaseInternal;
 
 impl LocationDataBaseInternal {
@@ -92,9 +124,9 @@ impl LocationDataBaseInternal {
         sqlx::query(
             r#"
             INSERT INTO location_mark(
-              id, location, radius
+              id, location, radius, capacity
             ) VALUES (
-              $1, ST_SETSRID(ST_POINT($2, $3), 4326), $4
+              $1, ST_SETSRID(ST_POINT($2, $3), 4326), $4, $5
             )
         "#,
         )
@@ -102,6 +134,7 @@ impl LocationDataBaseInternal {
         .bind(ctx.pos().x().as_ref())
         .bind(ctx.pos().y().as_ref())
         .bind(ctx.rad().as_ref())
+        .bind(ctx.capacity().as_ref())
         .execute(&mut *con)
         .await?;
 
@@ -134,13 +167,15 @@ impl LocationDataBaseInternal {
             r#"
             UPDATE location_mark
               SET location = ST_SETSRID(ST_POINT($1, $2), 4326),
-                  radius = $3
-            WHERE id = $4
+                  radius = $3,
+                  capacity = $4
+            WHERE id = $5
         "#,
         )
         .bind(ctx.pos().x().as_ref())
         .bind(ctx.pos().y().as_ref())
         .bind(ctx.rad().as_ref())
+        .bind(ctx.capacity().as_ref())
         .bind(ctx.id().as_ref())
         .execute(&mut *con)
         .await?;
@@ -212,7 +247,7 @@ impl LocationDataBaseInternal {
         // language=SQL
         let mark = sqlx::query_as::<_, LocationMarkRow>(
             r#"
-            SELECT id, location::GEOMETRY, radius FROM location_mark
+            SELECT id, location::GEOMETRY, radius, capacity FROM location_mark
         "#,
         )
         .fetch_all(&mut *con)
@@ -235,13 +270,67 @@ impl LocationDataBaseInternal {
                     .filter(|loc| loc.id.eq(&mark.id))
                     .map(|f| Localize::new(f.country.to_string(), f.name.to_string()))
                     .collect::<Result<Vec<_>, _>>()?;
-                Location::r#try(mark.id, mark.location.geometry.unwrap(), mark.radius, loc)
+                Location::r#try(mark.id, mark.location.geometry.unwrap(), mark.radius, mark.capacity, loc)
             })
             .collect::<Result<Vec<Location>, _>>()?;
 
         Ok(loc)
     }
 
+    pub(in crate::database) async fn find_nearby(
+        center: &Position,
+        within: &Distance,
+        limit: &Limit,
+        con: &mut PgConnection,
+    ) -> Result<Vec<(Location, Distance)>, DriverError> {
+        // language=SQL
+        let mark = sqlx::query_as::<_, NearbyLocationRow>(
+            r#"
+            WITH center AS (
+              SELECT ST_SetSRID(ST_Point($1, $2), 4326)::GEOGRAPHY AS point
+            )
+            SELECT id, location::GEOMETRY, radius, capacity, ST_Distance(location, center.point) AS distance
+              FROM location_mark, center
+             WHERE ST_DWithin(location, center.point, $3)
+             ORDER BY distance, id
+             LIMIT $4
+        "#,
+        )
+        .bind(center.x().as_ref())
+        .bind(center.y().as_ref())
+        .bind(within.as_ref())
+        .bind(limit.as_ref())
+        .fetch_all(&mut *con)
+        .await?;
+
+        let ids = mark.iter().map(|row| row.mark.id).collect::<Vec<_>>();
+
+        // language=SQL
+        let localize = sqlx::query_as::<_, LocationLocalizedRow>(
+            r#"
+            SELECT * FROM location_mark_localized_name WHERE id = ANY($1)
+        "#,
+        )
+        .bind(&ids)
+        .fetch_all(&mut *con)
+        .await?;
+
+        let loc = mark
+            .into_iter()
+            .map(|NearbyLocationRow { mark, distance }| {
+                let loc = localize
+                    .iter()
+                    .filter(|loc| loc.id.eq(&mark.id))
+                    .map(|f| Localize::new(f.country.to_string(), f.name.to_string()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let loc = Location::r#try(mark.id, mark.location.geometry.unwrap(), mark.radius, mark.capacity, loc)?;
+                Ok((loc, Distance::new(distance)))
+            })
+            .collect::<Result<Vec<_>, DriverError>>()?;
+
+        Ok(loc)
+    }
+
     pub(in crate::database) async fn find_by_id(
         id: &LocationId,
         con: &mut PgConnection,
@@ -249,7 +338,7 @@ impl LocationDataBaseInternal {
         // language=SQL
         let mark = sqlx::query_as::<_, LocationMarkRow>(
             r#"
-            SELECT id, location::GEOMETRY, radius FROM location_mark WHERE id = $1
+            SELECT id, location::GEOMETRY, radius, capacity FROM location_mark WHERE id = $1
         "#,
         )
         .bind(id.as_ref())
@@ -277,11 +366,12 @@ impl LocationDataBaseInternal {
             .transpose()?
             .unwrap();
         let rad = Radius::new(mark.radius);
+        let capacity = Capacity::new(mark.capacity)?;
         let loc = localize
             .into_iter()
             .map(|row| Localize::new(row.country, row.name))
             .collect::<Result<Vec<Localize>, _>>()?;
-        let loc = Location::new(lid, pos, rad, loc);
+        let loc = Location::new(lid, pos, rad, capacity, loc);
 
         Ok(Some(loc))
     }
@@ -291,6 +381,7 @@ impl LocationDataBaseInternal {
 mod tests {
     use crate::database::location::LocationDataBaseInternal;
     use kernel::entities::geology::{Position, Radius};
+    use kernel::entities::instance::Capacity;
     use kernel::entities::location::{Localize, LocalizeId, Location, LocationId};
     use sqlx::postgres::PgPoolOptions;
     use sqlx::{PgConnection, Pool, Postgres};
@@ -314,6 +405,7 @@ mod tests {
         let lid = LocationId::default();
         let pos = Position::new(132.76661710012877f64, 33.841405349477995f64)?;
         let rad = Radius::new(100);
+        let capacity = Capacity::default();
         let loc = vec![
             ("jp", "愛媛県庁第一別館"),
             ("en", "Ehime Prefecture Office Branch Office"),
@@ -321,7 +413,7 @@ mod tests {
         .into_iter()
         .map(|(c, n)| Localize::new(c, n))
         .collect::<Result<Vec<_>, _>>()?;
-        let loc = Location::new(lid, pos, rad, loc);
+        let loc = Location::new(lid, pos, rad, capacity, loc);
 
         LocationDataBaseInternal::create(&loc, &mut *con).await?;
         let loc = LocationDataBaseInternal::find_by_id(&lid, &mut *con).await?;
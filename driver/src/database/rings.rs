@@ -1,9 +1,17 @@
+use crate::database::audit::AuditLogDataBaseInternal;
 use crate::database::instance::RingRow;
+use crate::database::location::LocationLocalizedRow;
 use crate::error::DriverError;
 use async_trait::async_trait;
-use kernel::entities::ring::{Ring, RingId};
+use kernel::entities::audit::AuditEntry;
+use kernel::entities::instance::InstanceId;
+use kernel::entities::location::Localize;
+use kernel::entities::page::{Cursor, Limit, Page};
+use kernel::entities::ring::{Ring, RingId, RingRecord, UserId};
 use kernel::error::KernelError;
+use kernel::external::uuid::Uuid;
 use kernel::finder::RingFinder;
+use kernel::repository::RingRepository;
 use sqlx::{PgConnection, Pool, Postgres};
 
 pub struct RingDataBase {
@@ -23,6 +31,39 @@ impl RingFinder for RingDataBase {
         let found = RingDataBaseInternalProcessor::find_by_id(id, &mut con).await?;
         Ok(found)
     }
+
+    async fn find_by_user(&self, user: &UserId, after: Option<&Cursor>, limit: &Limit) -> Result<Page<RingRecord>, KernelError> {
+        let mut con = self.pool.acquire().await.map_err(DriverError::from)?;
+        let found = RingDataBaseInternalProcessor::find_by_user(user, after, limit, &mut con).await?;
+        Ok(found)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct RingRecordRow {
+    #[sqlx(flatten)]
+    ring: RingRow,
+    instance: Uuid,
+    has_image: bool,
+}
+
+#[async_trait]
+impl RingRepository for RingDataBase {
+    async fn update(&self, update: &Ring, audit: &AuditEntry) -> Result<(), KernelError> {
+        let mut transaction = self.pool.begin().await.map_err(DriverError::from)?;
+        RingDataBaseInternalProcessor::update(update, &mut transaction).await?;
+        AuditLogDataBaseInternal::record(audit, &mut transaction).await?;
+        transaction.commit().await.map_err(DriverError::from)?;
+        Ok(())
+    }
+
+    async fn delete(&self, delete: &RingId, audit: &AuditEntry) -> Result<(), KernelError> {
+        let mut transaction = self.pool.begin().await.map_err(DriverError::from)?;
+        RingDataBaseInternalProcessor::delete(delete, &mut transaction).await?;
+        AuditLogDataBaseInternal::record(audit, &mut transaction).await?;
+        transaction.commit().await.map_err(DriverError::from)?;
+        Ok(())
+    }
 }
 
 pub struct RingDataBaseInternalProcessor;
@@ -33,7 +74,7 @@ impl RingDataBaseInternalProcessor {
         // language=SQL
         let row = sqlx::query_as::<_, RingRow>(r#"
             SELECT
-                id, instance, pos_in::GEOMETRY, location, hue, user_id, index, created_at
+                id, instance, pos_in::GEOMETRY, location, hue, user_id, index, created_at, hidden_at
             FROM
                 rings
             WHERE
@@ -45,4 +86,166 @@ impl RingDataBaseInternalProcessor {
 
         row.map(TryInto::try_into).transpose()
     }
+
+    #[rustfmt::skip]
+    pub async fn find_by_user(user: &UserId, after: Option<&Cursor>, limit: &Limit, con: &mut PgConnection) -> Result<Page<RingRecord>, DriverError> {
+        // language=SQL
+        let rows = sqlx::query_as::<_, RingRecordRow>(r#"
+            SELECT
+                rings.id, rings.instance, rings.pos_in::GEOMETRY, rings.location, rings.hue,
+                rings.user_id, rings.index, rings.created_at, rings.hidden_at,
+                EXISTS (SELECT 1 FROM images WHERE images.id = rings.id) AS has_image
+            FROM
+                rings
+            WHERE
+                rings.user_id = $1
+              AND
+                rings.hidden_at IS NULL
+              AND
+                ($2::TIMESTAMPTZ IS NULL OR (rings.created_at, rings.id) < ($2, $3))
+            ORDER BY
+                rings.created_at DESC, rings.id DESC
+            LIMIT $4
+        "#)
+            .bind(user.as_ref())
+            .bind(after.map(|cursor| *cursor.at()))
+            .bind(after.map(|cursor| *cursor.id()))
+            // one more than the limit, to tell if there is a following page.
+            .bind(i64::from(*limit) + 1)
+            .fetch_all(&mut *con)
+            .await?;
+
+        let locations = rows.iter()
+            .map(|row| row.ring.location)
+            .collect::<Vec<Uuid>>();
+
+        // language=SQL
+        let localized = sqlx::query_as::<_, LocationLocalizedRow>(r#"
+            SELECT id, country, name FROM location_mark_localized_name WHERE id = ANY($1)
+        "#)
+            .bind(&locations)
+            .fetch_all(&mut *con)
+            .await?;
+
+        let records = rows.into_iter()
+            .map(|row| -> Result<RingRecord, DriverError> {
+                let localize = localized.iter()
+                    .filter(|loc| loc.id == row.ring.location)
+                    .map(|loc| Localize::new(&loc.country, &loc.name))
+                    .collect::<Result<Vec<_>, KernelError>>()?;
+                Ok(RingRecord::new(row.ring.try_into()?, InstanceId::new(row.instance), localize, row.has_image))
+            })
+            .collect::<Result<Vec<_>, DriverError>>()?;
+
+        Ok(Page::from_surplus(records, limit, |record| {
+            Cursor::new(*record.ring().created_at().as_ref(), *record.ring().id().as_ref())
+        }))
+    }
+
+    /// Fails with [`DriverError::Conflict`] if the ring has been deleted in the meantime,
+    /// so that the caller rolls back instead of recording a change that did not happen.
+    #[rustfmt::skip]
+    pub async fn update(update: &Ring, con: &mut PgConnection) -> Result<(), DriverError> {
+        // language=SQL
+        let result = sqlx::query(r#"
+            UPDATE rings
+              SET
+                hidden_at = $1
+            WHERE
+              id = $2
+        "#)
+            .bind(update.hidden_at().as_ref())
+            .bind(update.id().as_ref())
+            .execute(&mut *con)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(DriverError::Conflict { entity: "ring" });
+        }
+        Ok(())
+    }
+
+    /// Also advances the version of the instance,
+    /// so that an update based on a `RingSet` still holding the ring is rejected
+    /// instead of inserting it again.
+    /// Like [`Self::update`], fails if the ring is already gone.
+    #[rustfmt::skip]
+    pub async fn delete(delete: &RingId, con: &mut PgConnection) -> Result<(), DriverError> {
+        // language=SQL
+        sqlx::query(r#"
+            UPDATE instances
+              SET
+                version = version + 1
+            WHERE
+              id = (SELECT instance FROM rings WHERE id = $1)
+        "#)
+            .bind(delete.as_ref())
+            .execute(&mut *con)
+            .await?;
+
+        // language=SQL
+        let result = sqlx::query(r#"
+            DELETE FROM rings WHERE id = $1
+        "#)
+            .bind(delete.as_ref())
+            .execute(&mut *con)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(DriverError::Conflict { entity: "ring" });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::rings::RingDataBaseInternalProcessor;
+    use crate::error::DriverError;
+    use kernel::entities::geology::Position;
+    use kernel::entities::location::LocationId;
+    use kernel::entities::ring::{CreatedAt, HiddenAt, HueColor, Index, Ring, RingId, UserId};
+    use kernel::external::time::OffsetDateTime;
+    use sqlx::postgres::PgPoolOptions;
+    use sqlx::{Pool, Postgres};
+    use std::time::Duration;
+
+    async fn test_pool() -> anyhow::Result<Pool<Postgres>> {
+        dotenvy::dotenv().ok();
+
+        let url = dotenvy::var("PG_DATABASE_URL")
+            .expect("`PG_DATABASE_URL` is not set. This is a required environment variable.");
+        let pool = PgPoolOptions::new()
+            .max_connections(4)
+            .idle_timeout(Duration::new(5, 0))
+            .connect(&url)
+            .await?;
+
+        Ok(pool)
+    }
+
+    #[ignore = "It depends on Postgres and does not work as is."]
+    #[tokio::test]
+    async fn pg_moderate_missing_ring() -> anyhow::Result<()> {
+        let pool = test_pool().await?;
+        let mut transaction = pool.begin().await?;
+
+        let ring = Ring::new(
+            RingId::default(),
+            Position::new(132.7666f64, 33.8414f64)?,
+            LocationId::default(),
+            UserId::default(),
+            Index::new(0)?,
+            HueColor::new(0),
+            CreatedAt::new(OffsetDateTime::now_utc()),
+            HiddenAt::new(OffsetDateTime::now_utc()),
+        );
+
+        let updated = RingDataBaseInternalProcessor::update(&ring, &mut transaction).await;
+        assert!(matches!(updated, Err(DriverError::Conflict { entity: "ring" })));
+
+        let mut transaction = pool.begin().await?;
+        let deleted = RingDataBaseInternalProcessor::delete(ring.id(), &mut transaction).await;
+        assert!(matches!(deleted, Err(DriverError::Conflict { entity: "ring" })));
+
+        Ok(())
+    }
 }
@@ -10,12 +10,25 @@ pub enum DriverError {
     S3(anyhow::Error),
     #[error(transparent)]
     Redis(anyhow::Error),
+    #[error("`{entity}` was modified concurrently.")]
+    Conflict { entity: &'static str },
     #[error("Failed database initialization. {0}")]
     DataBaseInitialization(anyhow::Error),
     #[error(transparent)]
     Kernel(anyhow::Error),
 }
 
+impl DriverError {
+    /// Treat a unique violation as [`DriverError::Conflict`], since it means that
+    /// another request has stored the same row first.
+    pub(crate) fn conflict(e: sqlx::Error, entity: &'static str) -> Self {
+        match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => Self::Conflict { entity },
+            _ => Self::from(e),
+        }
+    }
+}
+
 impl From<KernelError> for DriverError {
     fn from(internal: KernelError) -> Self {
         Self::Kernel(anyhow::Error::new(internal))
@@ -55,6 +68,10 @@ impl From<DriverError> for KernelError {
             DriverError::Kernel(e) => Self::Internal(e),
             DriverError::DataBaseInitialization(e) => Self::Internal(e),
             DriverError::Decoding { .. } => Self::Driver(anyhow::Error::new(value)),
+            DriverError::Conflict { entity } => Self::Conflict {
+                entity,
+                msg: "It was modified by another request, please retry.",
+            },
         }
     }
 }
@@ -77,6 +94,7 @@ impl From<DriverError> for DriverErrorKind {
         match value {
             DriverError::Kernel(_) => Self::new("kernel", value),
             DriverError::Decoding { .. } => Self::new("decoding", value),
+            DriverError::Conflict { .. } => Self::new("conflict", value),
             _ => Self::new("database", value),
         }
     }
@@ -1,27 +1,43 @@
 use async_trait::async_trait;
-use kernel::entities::token::AdminToken;
+use kernel::entities::token::{AdminKeyId, AdminToken, KeyDigest, Scope};
 use kernel::error::KernelError;
+use kernel::external::time::OffsetDateTime;
+use kernel::repository::AdminKeyRepository;
 use kernel::security::AuthorizeAdminPolicy;
+use crate::database::AdminKeyDataBase;
 
+/// [`AuthorizeAdminPolicy`] against the admin keys stored in Postgres.
 #[derive(Clone)]
-pub struct AuthorizeInMemoryInstance {
-    token: AdminToken,
+pub struct AuthorizeAdminKey {
+    keys: AdminKeyDataBase,
 }
 
-impl AuthorizeInMemoryInstance {
-    pub fn new(token: AdminToken) -> AuthorizeInMemoryInstance {
-        Self { token }
+impl AuthorizeAdminKey {
+    pub fn new(keys: AdminKeyDataBase) -> AuthorizeAdminKey {
+        Self { keys }
     }
 }
 
 #[async_trait]
-impl AuthorizeAdminPolicy for AuthorizeInMemoryInstance {
-    async fn authorize(&self, token: &AdminToken) -> Result<(), KernelError> {
-        if self.token.eq(token) {
-            return Ok(());
+impl AuthorizeAdminPolicy for AuthorizeAdminKey {
+    async fn authorize(&self, token: &AdminToken, scope: &Scope) -> Result<AdminKeyId, KernelError> {
+        let now = OffsetDateTime::now_utc();
+        let Some(key) = self.keys.find_by_digest(&KeyDigest::of(token)).await? else {
+            return Err(KernelError::Validation {
+                msg: "Invalid authorize token.",
+            });
+        };
+        if !key.is_active(now) {
+            return Err(KernelError::Validation {
+                msg: "Authorize token is revoked or expired.",
+            });
         }
-        Err(KernelError::Validation {
-            msg: "Invalid authorize token.",
-        })
+        self.keys.touch(key.id(), &now).await?;
+        if !key.allows(scope) {
+            return Err(KernelError::Forbidden {
+                scope: scope.as_str(),
+            });
+        }
+        Ok(*key.id())
     }
 }
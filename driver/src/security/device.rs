@@ -0,0 +1,98 @@
+use hmac::{Hmac, Mac};
+use kernel::entities::ring::UserId;
+use kernel::entities::token::DeviceToken;
+use kernel::error::KernelError;
+use kernel::external::uuid::Uuid;
+use kernel::security::DeviceIdentity;
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// [`DeviceIdentity`] as `{user}.{HMAC-SHA256 of user}` in hex, signed with a secret shared by every server node.
+#[derive(Clone)]
+pub struct HmacDeviceIdentity {
+    secret: Vec<u8>,
+}
+
+impl HmacDeviceIdentity {
+    /// Shorter secrets are rejected, as anyone who guesses it can sign tokens for any user.
+    pub const MIN_SECRET_LEN: usize = 32;
+
+    pub fn new(secret: impl Into<Vec<u8>>) -> Result<HmacDeviceIdentity, KernelError> {
+        let secret = secret.into();
+        if secret.len() < Self::MIN_SECRET_LEN {
+            return Err(KernelError::Validation {
+                msg: "device token secret should be at least 32 bytes.",
+            });
+        }
+        Ok(Self { secret })
+    }
+
+    fn mac(&self, user: &Uuid) -> HmacSha256 {
+        // Why `.expect()`?: Because HMAC accepts keys of any length.
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length.");
+        mac.update(user.as_bytes());
+        mac
+    }
+}
+
+impl DeviceIdentity for HmacDeviceIdentity {
+    fn issue(&self) -> (UserId, DeviceToken) {
+        let user = UserId::default();
+        let signature = self.mac(user.as_ref()).finalize().into_bytes();
+        let token = DeviceToken::new(format!("{}.{signature:x}", user.as_ref()));
+        (user, token)
+    }
+
+    fn verify(&self, token: &DeviceToken) -> Result<UserId, KernelError> {
+        let invalid = || KernelError::Validation {
+            msg: "Invalid device token.",
+        };
+        let (user, signature) = token.as_ref().split_once('.').ok_or_else(invalid)?;
+        let user = user.parse::<Uuid>().map_err(|_| invalid())?;
+        let signature = decode_hex(signature).ok_or_else(invalid)?;
+        self.mac(&user)
+            .verify_slice(&signature)
+            .map_err(|_| invalid())?;
+        Ok(UserId::new(user))
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [_, _] => std::str::from_utf8(pair).ok().and_then(|pair| u8::from_str_radix(pair, 16).ok()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_test() -> anyhow::Result<()> {
+        let identity = HmacDeviceIdentity::new("0123456789abcdef0123456789abcdef")?;
+        let (user, token) = identity.issue();
+        assert_eq!(identity.verify(&token)?, user);
+
+        // Signed by another server.
+        let other = HmacDeviceIdentity::new("fedcba9876543210fedcba9876543210")?;
+        assert!(other.verify(&token).is_err());
+
+        // Signature moved to another user.
+        let (_, signature) = token.as_ref().split_once('.').unwrap();
+        let forged = DeviceToken::new(format!("{}.{signature}", Uuid::new_v4()));
+        assert!(identity.verify(&forged).is_err());
+
+        assert!(identity.verify(&DeviceToken::new(user.as_ref().to_string())).is_err());
+        assert!(identity.verify(&DeviceToken::new(format!("{}.zz", user.as_ref()))).is_err());
+        assert!(HmacDeviceIdentity::new("short").is_err());
+        Ok(())
+    }
+}
@@ -22,8 +22,12 @@ impl S3ImageStorageService {
 impl ImageExportExternalStorageService for S3ImageStorageService {
     async fn export(&self, target: &Image, metadata: MetaData) -> Result<(), KernelError> {
         S3ImageStorageServiceInternalProcessor::export(target, metadata, &self.bucket)
-            .await
-            .map_err(DriverError::from)?;
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, target: &Image) -> Result<(), KernelError> {
+        S3ImageStorageServiceInternalProcessor::delete(target, &self.bucket).await?;
         Ok(())
     }
 }
@@ -72,4 +76,10 @@ impl S3ImageStorageServiceInternalProcessor {
 
         Ok(())
     }
+
+    pub async fn delete(target: &Image, buc: &Bucket) -> Result<(), DriverError> {
+        buc.delete_object(format!("/{}.png", target.created_at())).await?;
+        buc.delete_object(format!("/{}.meta.json", target.created_at())).await?;
+        Ok(())
+    }
 }
@@ -1,3 +1,9 @@
+mod event;
 mod location;
+mod presence;
+mod rate;
 
-pub use self::location::*;
\ No newline at end of file
+pub use self::event::*;
+pub use self::location::*;
+pub use self::presence::*;
+pub use self::rate::*;
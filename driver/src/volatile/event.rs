@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use async_trait::async_trait;
+use deadpool_redis::{redis, Connection, Pool};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast::{self, error::RecvError, Sender};
+use kernel::entities::location::LocationId;
+use kernel::entities::volatiles::Event;
+use kernel::error::KernelError;
+use kernel::volatiles::{EventBus, EventStream};
+use crate::error::DriverError;
+
+/// [`EventBus`] over Redis pub/sub, shared by every server node connected to the same Redis.
+pub struct RedisEventBus {
+    pool: Pool,
+}
+
+impl RedisEventBus {
+    const CHANNEL: &'static str = "event_bus";
+    const SEQ_NAMESPACE: &'static str = "event_bus_seq";
+
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl EventBus for RedisEventBus {
+    async fn publish(&self, location: &LocationId, payload: Value) -> Result<u64, KernelError> {
+        let mut con = self.pool.get().await.map_err(DriverError::from)?;
+        let seq = RedisEventInternal::publish(Self::CHANNEL, Self::SEQ_NAMESPACE, location, payload, &mut con).await?;
+        Ok(seq)
+    }
+
+    async fn subscribe(&self) -> Result<EventStream, KernelError> {
+        let con = self.pool.get().await.map_err(DriverError::from)?;
+        let stream = RedisEventInternal::subscribe(Self::CHANNEL, con).await?;
+        Ok(stream)
+    }
+}
+
+/// Body of a published message, following `{seq}|`.
+#[derive(Deserialize, Serialize)]
+struct Published {
+    location: LocationId,
+    payload: Value,
+}
+
+pub(in crate) struct RedisEventInternal;
+
+impl RedisEventInternal {
+    /// Numbering and publishing in one script, so that messages are published in the order of `seq`.
+    // language=Lua
+    const PUBLISH: &'static str = r#"
+        local seq = redis.call('INCR', KEYS[1])
+        redis.call('PUBLISH', ARGV[1], seq .. '|' .. ARGV[2])
+        return seq
+    "#;
+
+    pub async fn publish(
+        channel: &str,
+        namespace: &str,
+        location: &LocationId,
+        payload: Value,
+        con: &mut Connection,
+    ) -> Result<u64, DriverError> {
+        let body = serde_json::to_string(&Published { location: *location, payload })
+            .map_err(|e| DriverError::Redis(anyhow::Error::new(e)))?;
+        let seq = redis::cmd("EVAL")
+            .arg(Self::PUBLISH)
+            .arg(1)
+            .arg(format!("{namespace}:{}", location.as_ref()))
+            .arg(channel)
+            .arg(body)
+            .query_async::<_, u64>(&mut *con)
+            .await?;
+        Ok(seq)
+    }
+
+    pub async fn subscribe(channel: &str, con: Connection) -> Result<EventStream, DriverError> {
+        // Why `take`?: Because a connection in subscriber mode cannot go back to the pool.
+        let mut pubsub = Connection::take(con).into_pubsub();
+        pubsub.subscribe(channel).await?;
+        let stream = pubsub
+            .into_on_message()
+            .filter_map(|msg| async move {
+                let event = msg
+                    .get_payload::<String>()
+                    .ok()
+                    .and_then(|raw| Self::decode(&raw));
+                if event.is_none() {
+                    tracing::error!("dropped malformed message on `{}`.", msg.get_channel_name());
+                }
+                event
+            })
+            .boxed();
+        Ok(stream)
+    }
+
+    fn decode(raw: &str) -> Option<Event> {
+        let (seq, body) = raw.split_once('|')?;
+        let seq = seq.parse::<u64>().ok()?;
+        let Published { location, payload } = serde_json::from_str(body).ok()?;
+        Some(Event::new(location, seq, payload))
+    }
+}
+
+/// [`EventBus`] within this process, for running a single node without Redis round trips.
+pub struct InProcessEventBus {
+    sender: Sender<Event>,
+    seq: Mutex<HashMap<LocationId, u64>>,
+}
+
+impl InProcessEventBus {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sender: broadcast::channel(capacity).0,
+            seq: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for InProcessEventBus {
+    fn default() -> Self {
+        Self::new(64)
+    }
+}
+
+#[async_trait]
+impl EventBus for InProcessEventBus {
+    async fn publish(&self, location: &LocationId, payload: Value) -> Result<u64, KernelError> {
+        // Sent while locked, so that subscribers receive them in the order of `seq`.
+        let mut numbers = self.seq.lock().unwrap();
+        let seq = numbers.entry(*location).or_default();
+        *seq += 1;
+        // Having no subscribers is not an error here.
+        let _ = self.sender.send(Event::new(*location, *seq, payload));
+        Ok(*seq)
+    }
+
+    async fn subscribe(&self) -> Result<EventStream, KernelError> {
+        let receiver = self.sender.subscribe();
+        let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    Err(RecvError::Lagged(n)) => tracing::warn!("event bus subscriber skipped {n} events."),
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn in_process_test() -> anyhow::Result<()> {
+        let bus = InProcessEventBus::default();
+        let (a, b) = (LocationId::default(), LocationId::default());
+        let mut events = bus.subscribe().await?;
+
+        assert_eq!(bus.publish(&a, json!({ "n": 1 })).await?, 1);
+        assert_eq!(bus.publish(&b, json!({ "n": 2 })).await?, 1);
+        assert_eq!(bus.publish(&a, json!({ "n": 3 })).await?, 2);
+
+        let received = events.by_ref().take(3).collect::<Vec<_>>().await;
+        assert_eq!(received[0], Event::new(a, 1, json!({ "n": 1 })));
+        assert_eq!(received[1], Event::new(b, 1, json!({ "n": 2 })));
+        assert_eq!(received[2], Event::new(a, 2, json!({ "n": 3 })));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_test() {
+        let location = LocationId::default();
+        let raw = format!(r#"12|{{"location":"{}","payload":{{"type":"ping"}}}}"#, location.as_ref());
+        let event = RedisEventInternal::decode(&raw).unwrap();
+        assert_eq!(event, Event::new(location, 12, json!({ "type": "ping" })));
+
+        assert!(RedisEventInternal::decode("12").is_none());
+        assert!(RedisEventInternal::decode("x|{}").is_none());
+    }
+}
@@ -19,19 +19,19 @@ impl LocationEtagVolatileDataBase {
 impl LocationETagCache for LocationEtagVolatileDataBase {
     async fn save(&self, tag: Etag) -> Result<(), KernelError> {
         let mut con = self.pool.get().await.map_err(DriverError::from)?;
-        Internal::save(Self::NAMESPACE, tag, &mut con).await.map_err(DriverError::from)?;
+        Internal::save(Self::NAMESPACE, tag, &mut con).await?;
         Ok(())
     }
 
     async fn dele(&self) -> Result<(), KernelError> {
         let mut con = self.pool.get().await.map_err(DriverError::from)?;
-        Internal::dele(Self::NAMESPACE, &mut con).await.map_err(DriverError::from)?;
+        Internal::dele(Self::NAMESPACE, &mut con).await?;
         Ok(())
     }
 
     async fn find(&self) -> Result<Option<Etag>, KernelError> {
         let mut con = self.pool.get().await.map_err(DriverError::from)?;
-        let found = Internal::find(Self::NAMESPACE, &mut con).await.map_err(DriverError::from)?;
+        let found = Internal::find(Self::NAMESPACE, &mut con).await?;
         Ok(found)
     }
 }
@@ -44,7 +44,7 @@ impl Internal {
         redis::cmd("SET")
             .arg(key)
             .arg(tag.as_ref())
-            .query_async(&mut *con)
+            .query_async::<_, ()>(&mut *con)
             .await?;
         Ok(())
     }
@@ -52,7 +52,7 @@ impl Internal {
     pub async fn dele(key: &str, con: &mut RedisConnection) -> Result<(), DriverError> {
         redis::cmd("DEL")
             .arg(key)
-            .query_async(&mut *con)
+            .query_async::<_, ()>(&mut *con)
             .await?;
         Ok(())
     }
@@ -0,0 +1,204 @@
+use async_trait::async_trait;
+use deadpool_redis::{redis, Connection as RedisConnection, Pool};
+use futures::StreamExt;
+use kernel::entities::location::LocationId;
+use kernel::entities::volatiles::{Presence, SessionId, Viewers};
+use kernel::error::KernelError;
+use kernel::external::uuid::Uuid;
+use kernel::volatiles::{PresenceStream, PresenceTracker};
+use crate::error::DriverError;
+
+/// [`PresenceTracker`] in Redis, counting the sessions of every server node together.
+///
+/// Each location is a sorted set of sessions, scored by when they stop being counted.
+/// Changed counts are published on their own channel, apart from the event bus.
+pub struct RedisPresenceTracker {
+    pool: Pool,
+}
+
+impl RedisPresenceTracker {
+    const CHANNEL: &'static str = "presence";
+
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+
+    fn key(location: &LocationId) -> String {
+        format!("{}:{}", Self::NAMESPACE, location.as_ref())
+    }
+
+    fn ttl() -> i64 {
+        Self::TTL.whole_milliseconds() as i64
+    }
+}
+
+#[async_trait]
+impl PresenceTracker for RedisPresenceTracker {
+    async fn enter(&self, location: &LocationId, session: &SessionId) -> Result<Viewers, KernelError> {
+        let mut con = self.pool.get().await.map_err(DriverError::from)?;
+        let viewers = RedisPresenceInternal::update(Self::CHANNEL, location, &Self::key(location), "enter", Some(session), Self::ttl(), &mut con).await?;
+        Ok(viewers)
+    }
+
+    async fn leave(&self, location: &LocationId, session: &SessionId) -> Result<Viewers, KernelError> {
+        let mut con = self.pool.get().await.map_err(DriverError::from)?;
+        let viewers = RedisPresenceInternal::update(Self::CHANNEL, location, &Self::key(location), "leave", Some(session), Self::ttl(), &mut con).await?;
+        Ok(viewers)
+    }
+
+    async fn count(&self, location: &LocationId) -> Result<Viewers, KernelError> {
+        let mut con = self.pool.get().await.map_err(DriverError::from)?;
+        let viewers = RedisPresenceInternal::update(Self::CHANNEL, location, &Self::key(location), "count", None, Self::ttl(), &mut con).await?;
+        Ok(viewers)
+    }
+
+    async fn subscribe(&self) -> Result<PresenceStream, KernelError> {
+        let con = self.pool.get().await.map_err(DriverError::from)?;
+        let stream = RedisPresenceInternal::subscribe(Self::CHANNEL, con).await?;
+        Ok(stream)
+    }
+}
+
+pub(in crate) struct RedisPresenceInternal;
+
+impl RedisPresenceInternal {
+    /// Drops the expired sessions before counting, on the clock of Redis.
+    /// The count is published as `{location}|{count}` if any session was added or removed.
+    // language=Lua
+    const UPDATE: &'static str = r#"
+        local clock = redis.call('TIME')
+        local now = tonumber(clock[1]) * 1000 + math.floor(tonumber(clock[2]) / 1000)
+        local ttl = tonumber(ARGV[3])
+        local changed = redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now)
+        if ARGV[1] == 'enter' then
+          changed = changed + redis.call('ZADD', KEYS[1], now + ttl, ARGV[2])
+          redis.call('PEXPIRE', KEYS[1], ttl)
+        elseif ARGV[1] == 'leave' then
+          changed = changed + redis.call('ZREM', KEYS[1], ARGV[2])
+        end
+        local count = redis.call('ZCARD', KEYS[1])
+        if changed > 0 then
+          redis.call('PUBLISH', ARGV[4], ARGV[5] .. '|' .. count)
+        end
+        return count
+    "#;
+
+    pub async fn update(
+        channel: &str,
+        location: &LocationId,
+        key: &str,
+        op: &str,
+        session: Option<&SessionId>,
+        ttl: i64,
+        con: &mut RedisConnection,
+    ) -> Result<Viewers, DriverError> {
+        let count = redis::cmd("EVAL")
+            .arg(Self::UPDATE)
+            .arg(1)
+            .arg(key)
+            .arg(op)
+            .arg(session.map(|session| session.to_string()).unwrap_or_default())
+            .arg(ttl)
+            .arg(channel)
+            .arg(location.as_ref().to_string())
+            .query_async::<_, u64>(&mut *con)
+            .await?;
+        Ok(Viewers::new(count))
+    }
+
+    pub async fn subscribe(channel: &str, con: RedisConnection) -> Result<PresenceStream, DriverError> {
+        // Why `take`?: Because a connection in subscriber mode cannot go back to the pool.
+        let mut pubsub = RedisConnection::take(con).into_pubsub();
+        pubsub.subscribe(channel).await?;
+        let stream = pubsub
+            .into_on_message()
+            .filter_map(|msg| async move {
+                let presence = msg
+                    .get_payload::<String>()
+                    .ok()
+                    .and_then(|raw| Self::decode(&raw));
+                if presence.is_none() {
+                    tracing::error!("dropped malformed message on `{}`.", msg.get_channel_name());
+                }
+                presence
+            })
+            .boxed();
+        Ok(stream)
+    }
+
+    fn decode(raw: &str) -> Option<Presence> {
+        let (location, count) = raw.split_once('|')?;
+        let location = location.parse::<Uuid>().ok()?;
+        let count = count.parse::<u64>().ok()?;
+        Some(Presence::new(LocationId::new(location), Viewers::new(count)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DataBaseInitializer;
+    use std::time::Duration;
+
+    async fn test_pool() -> anyhow::Result<Pool> {
+        dotenvy::dotenv().ok();
+
+        let url = dotenvy::var("REDIS_URL")
+            .expect("`REDIS_URL` is not set. This is a required environment variable.");
+        Ok(DataBaseInitializer::setup_redis(url).await?)
+    }
+
+    #[test]
+    fn decode_test() {
+        let location = LocationId::default();
+        let presence = RedisPresenceInternal::decode(&format!("{}|3", location.as_ref())).unwrap();
+        assert_eq!(presence, Presence::new(location, Viewers::new(3u64)));
+
+        assert!(RedisPresenceInternal::decode("3").is_none());
+        assert!(RedisPresenceInternal::decode(&format!("{}|x", location.as_ref())).is_none());
+    }
+
+    #[ignore = "It depends on Redis and does not work as is."]
+    #[tokio::test]
+    async fn redis_enter_leave() -> anyhow::Result<()> {
+        let tracker = RedisPresenceTracker::new(test_pool().await?);
+        let changes = tracker.subscribe().await?;
+        let location = LocationId::default();
+        let (a, b) = (SessionId::new(Uuid::new_v4()), SessionId::new(Uuid::new_v4()));
+
+        assert_eq!(tracker.enter(&location, &a).await?, Viewers::new(1u64));
+        assert_eq!(tracker.enter(&location, &b).await?, Viewers::new(2u64));
+        // Entering again only keeps it counted.
+        assert_eq!(tracker.enter(&location, &a).await?, Viewers::new(2u64));
+        assert_eq!(tracker.leave(&location, &a).await?, Viewers::new(1u64));
+        assert_eq!(tracker.leave(&location, &b).await?, Viewers::new(0u64));
+        assert_eq!(tracker.count(&location).await?, Viewers::new(0u64));
+
+        let published = changes.filter(|presence| {
+            let matched = presence.location() == &location;
+            async move { matched }
+        });
+        let counts = published.take(4).map(|presence| u64::from(presence.viewers())).collect::<Vec<_>>().await;
+        assert_eq!(counts, [1, 2, 1, 0]);
+        Ok(())
+    }
+
+    #[ignore = "It depends on Redis and does not work as is."]
+    #[tokio::test]
+    async fn redis_expire() -> anyhow::Result<()> {
+        let tracker = RedisPresenceTracker::new(test_pool().await?);
+        let location = LocationId::default();
+        let key = RedisPresenceTracker::key(&location);
+        let session = SessionId::new(Uuid::new_v4());
+        let mut con = tracker.pool.get().await?;
+
+        let channel = RedisPresenceTracker::CHANNEL;
+        let viewers = RedisPresenceInternal::update(channel, &location, &key, "enter", Some(&session), 100, &mut con).await?;
+        assert_eq!(viewers, Viewers::new(1u64));
+
+        // Not entered again within the TTL, as if its server node crashed.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(tracker.count(&location).await?, Viewers::new(0u64));
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use async_trait::async_trait;
+use deadpool_redis::{redis, Connection as RedisConnection, Pool};
+use kernel::entities::volatiles::{Admission, RateKey, RateLimit};
+use kernel::error::KernelError;
+use kernel::external::time::Duration;
+use kernel::volatiles::RateLimiter;
+use crate::error::DriverError;
+
+/// [`RateLimiter`] shared through Redis, so that every server node counts the same buckets.
+///
+/// While Redis is unreachable it counts within this process instead, rather than letting everything through.
+pub struct RedisRateLimiter {
+    pool: Pool,
+    fallback: InMemoryRateLimiter,
+}
+
+impl RedisRateLimiter {
+    pub fn new(pool: Pool) -> Self {
+        Self {
+            pool,
+            fallback: InMemoryRateLimiter::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimiter for RedisRateLimiter {
+    async fn acquire(&self, key: &RateKey, limit: &RateLimit) -> Result<Admission, KernelError> {
+        let acquired = async {
+            let mut con = self.pool.get().await?;
+            RedisRateInternal::acquire(&format!("{}:{key}", Self::NAMESPACE), limit, &mut con).await
+        }
+        .await;
+
+        match acquired {
+            Ok(admission) => Ok(admission),
+            Err(e) => {
+                tracing::warn!("rate limit falls back to in-memory buckets. {e}");
+                self.fallback.acquire(key, limit).await
+            }
+        }
+    }
+}
+
+pub(in crate) struct RedisRateInternal;
+
+impl RedisRateInternal {
+    /// Same arithmetic as [`RateLimit::take`], on the clock of Redis. Returns the microseconds to wait.
+    // language=Lua
+    const ACQUIRE: &'static str = r#"
+        local burst = tonumber(ARGV[1])
+        local refill = tonumber(ARGV[2])
+        local clock = redis.call('TIME')
+        local now = tonumber(clock[1]) * 1000000 + tonumber(clock[2])
+        local state = redis.call('HMGET', KEYS[1], 'tokens', 'at')
+        local tokens = tonumber(state[1]) or burst
+        local at = tonumber(state[2]) or now
+        tokens = math.min(burst, tokens + math.max(0, now - at) / refill)
+        local wait = 0
+        if tokens >= 1 then
+          tokens = tokens - 1
+        else
+          wait = math.ceil((1 - tokens) * refill)
+        end
+        redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'at', now)
+        redis.call('PEXPIRE', KEYS[1], math.ceil(burst * refill / 1000) + 1000)
+        return wait
+    "#;
+
+    pub async fn acquire(key: &str, limit: &RateLimit, con: &mut RedisConnection) -> Result<Admission, DriverError> {
+        let refill = limit.refill().whole_microseconds().max(1) as u64;
+        let wait = redis::cmd("EVAL")
+            .arg(Self::ACQUIRE)
+            .arg(1)
+            .arg(key)
+            .arg(limit.burst())
+            .arg(refill)
+            .query_async::<_, i64>(&mut *con)
+            .await?;
+        if wait <= 0 {
+            return Ok(Admission::Allowed);
+        }
+        Ok(Admission::Limited {
+            retry_after: Duration::microseconds(wait),
+        })
+    }
+}
+
+/// [`RateLimiter`] within this process.
+pub struct InMemoryRateLimiter {
+    buckets: Mutex<HashMap<RateKey, (f64, Instant)>>,
+}
+
+impl InMemoryRateLimiter {
+    /// Above this many buckets, the ones that have filled up again are dropped.
+    const PRUNE_ABOVE: usize = 10_000;
+}
+
+impl Default for InMemoryRateLimiter {
+    fn default() -> Self {
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimiter for InMemoryRateLimiter {
+    async fn acquire(&self, key: &RateKey, limit: &RateLimit) -> Result<Admission, KernelError> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() > Self::PRUNE_ABOVE {
+            // A full bucket is the same as no bucket.
+            buckets.retain(|_, (tokens, at)| limit.take(*tokens, elapsed(now, *at)).map_or(true, |left| left + 1.0 < f64::from(limit.burst())));
+        }
+
+        let (tokens, at) = buckets
+            .entry(key.clone())
+            .or_insert_with(|| (f64::from(limit.burst()), now));
+        match limit.take(*tokens, elapsed(now, *at)) {
+            Ok(left) => {
+                (*tokens, *at) = (left, now);
+                Ok(Admission::Allowed)
+            }
+            Err(retry_after) => Ok(Admission::Limited { retry_after }),
+        }
+    }
+}
+
+fn elapsed(now: Instant, at: Instant) -> Duration {
+    Duration::try_from(now.duration_since(at)).unwrap_or(Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::entities::ring::UserId;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[tokio::test]
+    async fn in_memory_test() -> anyhow::Result<()> {
+        let limiter = InMemoryRateLimiter::default();
+        let limit = RateLimit::per_minute(2);
+        let user = RateKey::user(&UserId::default());
+        let addr = RateKey::addr(&IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        assert_eq!(limiter.acquire(&user, &limit).await?, Admission::Allowed);
+        assert_eq!(limiter.acquire(&user, &limit).await?, Admission::Allowed);
+        let Admission::Limited { retry_after } = limiter.acquire(&user, &limit).await? else {
+            panic!("third request within a minute should be limited.");
+        };
+        assert!(retry_after > Duration::seconds(29) && retry_after <= Duration::seconds(30));
+
+        // Buckets are independent.
+        assert_eq!(limiter.acquire(&addr, &limit).await?, Admission::Allowed);
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
+pub mod audit;
 pub mod geology;
 pub mod image;
 pub mod instance;
 pub mod location;
+pub mod page;
 pub mod ring;
 pub mod token;
 pub mod volatiles;
@@ -0,0 +1,125 @@
+mod action;
+mod filter;
+
+pub use self::{action::*, filter::*};
+use crate::entities::token::AdminKeyId;
+use crate::error::KernelError;
+use destructure::Destructure;
+use serde::Serialize;
+use serde_json::Value;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Record of an admin mutation, and the state of its target around it.
+#[derive(Debug, Clone, Destructure)]
+pub struct AuditEntry {
+    id: Uuid,
+    actor: AdminKeyId,
+    action: AuditAction,
+    target: Uuid,
+    before: Option<Value>,
+    after: Option<Value>,
+    recorded_at: OffsetDateTime,
+}
+
+impl AuditEntry {
+    pub fn new(
+        id: Uuid,
+        actor: AdminKeyId,
+        action: AuditAction,
+        target: Uuid,
+        before: Option<Value>,
+        after: Option<Value>,
+        recorded_at: OffsetDateTime,
+    ) -> AuditEntry {
+        Self {
+            id,
+            actor,
+            action,
+            target,
+            before,
+            after,
+            recorded_at,
+        }
+    }
+
+    /// Record `action` by `actor` on `target` now. `before` is absent on creation, `after` on deletion.
+    pub fn record<T: Serialize>(
+        actor: AdminKeyId,
+        action: AuditAction,
+        target: impl Into<Uuid>,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<AuditEntry, KernelError> {
+        let to_value = |state: Option<&T>| {
+            state
+                .map(serde_json::to_value)
+                .transpose()
+                .map_err(|e| KernelError::Internal(anyhow::Error::new(e)))
+        };
+        Ok(Self::new(
+            Uuid::new_v4(),
+            actor,
+            action,
+            target.into(),
+            to_value(before)?,
+            to_value(after)?,
+            OffsetDateTime::now_utc(),
+        ))
+    }
+}
+
+impl AuditEntry {
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn actor(&self) -> &AdminKeyId {
+        &self.actor
+    }
+
+    pub fn action(&self) -> &AuditAction {
+        &self.action
+    }
+
+    pub fn target(&self) -> &Uuid {
+        &self.target
+    }
+
+    pub fn before(&self) -> &Option<Value> {
+        &self.before
+    }
+
+    pub fn after(&self) -> &Option<Value> {
+        &self.after
+    }
+
+    pub fn recorded_at(&self) -> &OffsetDateTime {
+        &self.recorded_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn record_test() -> anyhow::Result<()> {
+        let target = Uuid::new_v4();
+        let entry = AuditEntry::record(
+            AdminKeyId::default(),
+            AuditAction::LocationUpdate,
+            target,
+            Some(&json!({ "radius": 100 })),
+            Some(&json!({ "radius": 150 })),
+        )?;
+        assert_eq!(entry.target(), &target);
+        assert_eq!(entry.after().as_ref().map(|after| &after["radius"]), Some(&json!(150)));
+
+        for action in AuditAction::VARIANTS {
+            assert_eq!(action.as_str().parse::<AuditAction>()?, action);
+        }
+        Ok(())
+    }
+}
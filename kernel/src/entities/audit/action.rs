@@ -0,0 +1,58 @@
+use crate::error::KernelError;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Admin mutation recorded in an [`AuditEntry`](super::AuditEntry).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum AuditAction {
+    LocationCreate,
+    LocationUpdate,
+    LocationDelete,
+    /// Only one localized name of a location was deleted.
+    LocalizeDelete,
+    RingHide,
+    RingRestore,
+    RingDelete,
+}
+
+impl AuditAction {
+    pub const VARIANTS: [AuditAction; 7] = [
+        AuditAction::LocationCreate,
+        AuditAction::LocationUpdate,
+        AuditAction::LocationDelete,
+        AuditAction::LocalizeDelete,
+        AuditAction::RingHide,
+        AuditAction::RingRestore,
+        AuditAction::RingDelete,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::LocationCreate => "location.create",
+            AuditAction::LocationUpdate => "location.update",
+            AuditAction::LocationDelete => "location.delete",
+            AuditAction::LocalizeDelete => "location.delete_localize",
+            AuditAction::RingHide => "ring.hide",
+            AuditAction::RingRestore => "ring.restore",
+            AuditAction::RingDelete => "ring.delete",
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = KernelError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::VARIANTS
+            .into_iter()
+            .find(|action| action.as_str() == s)
+            .ok_or(KernelError::Validation {
+                msg: "unknown audit action.",
+            })
+    }
+}
+
+impl Display for AuditAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::entities::audit::AuditAction;
+use crate::entities::instance::Period;
+use crate::entities::token::AdminKeyId;
+use uuid::Uuid;
+
+/// Conditions to narrow down the audit log. Unset conditions match everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    actor: Option<AdminKeyId>,
+    action: Option<AuditAction>,
+    target: Option<Uuid>,
+    recorded: Period,
+}
+
+impl AuditFilter {
+    pub fn new(
+        actor: Option<AdminKeyId>,
+        action: Option<AuditAction>,
+        target: Option<Uuid>,
+        recorded: Period,
+    ) -> AuditFilter {
+        Self {
+            actor,
+            action,
+            target,
+            recorded,
+        }
+    }
+
+    pub fn actor(&self) -> &Option<AdminKeyId> {
+        &self.actor
+    }
+
+    pub fn action(&self) -> &Option<AuditAction> {
+        &self.action
+    }
+
+    pub fn target(&self) -> &Option<Uuid> {
+        &self.target
+    }
+
+    pub fn recorded(&self) -> &Period {
+        &self.recorded
+    }
+}
@@ -1,6 +1,7 @@
+mod distance;
 mod latitude;
 mod longitude;
 mod position;
 mod radius;
 
-pub use self::{latitude::*, longitude::*, position::*, radius::*};
+pub use self::{distance::*, latitude::*, longitude::*, position::*, radius::*};
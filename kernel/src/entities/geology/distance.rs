@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+use super::{Position, Radius};
+
+/// WGS84 semi-major axis (meters).
+const WGS84_A: f64 = 6_378_137.0;
+/// WGS84 flattening.
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+/// WGS84 semi-minor axis (meters).
+const WGS84_B: f64 = WGS84_A * (1.0 - WGS84_F);
+
+/// Geodesic distance on the WGS84 ellipsoid in meters.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Deserialize, Serialize)]
+pub struct Distance(f64);
+
+impl Distance {
+    pub fn new(meters: impl Into<f64>) -> Distance {
+        Self(meters.into())
+    }
+
+    /// Calculate the distance between two points using Vincenty's inverse formula.
+    ///
+    /// Vincenty does not converge for nearly antipodal points,
+    /// in that case it falls back to the great-circle distance on a sphere of mean radius.
+    pub fn between(from: &Position, to: &Position) -> Distance {
+        let (lon1, lat1) = (from.x().as_ref().to_radians(), from.y().as_ref().to_radians());
+        let (lon2, lat2) = (to.x().as_ref().to_radians(), to.y().as_ref().to_radians());
+
+        let l = lon2 - lon1;
+        let u1 = ((1.0 - WGS84_F) * lat1.tan()).atan();
+        let u2 = ((1.0 - WGS84_F) * lat2.tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let mut lambda = l;
+        for _ in 0..200 {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+
+            if sin_sigma == 0.0 {
+                // coincident points
+                return Self(0.0);
+            }
+
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos_sq_alpha = 1.0 - sin_alpha.powi(2);
+            // on the equatorial line `cos_sq_alpha` is 0.
+            let cos_2sigma_m = if cos_sq_alpha != 0.0 {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+            } else {
+                0.0
+            };
+            let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+
+            let prev = lambda;
+            lambda = l
+                + (1.0 - c)
+                    * WGS84_F
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos_2sigma_m
+                                + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+
+            if (lambda - prev).abs() < 1e-12 {
+                let u_sq = cos_sq_alpha * (WGS84_A.powi(2) - WGS84_B.powi(2)) / WGS84_B.powi(2);
+                let a = 1.0
+                    + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+                let b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+                let delta_sigma = b
+                    * sin_sigma
+                    * (cos_2sigma_m
+                        + b / 4.0
+                            * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                                - b / 6.0
+                                    * cos_2sigma_m
+                                    * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                    * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+                return Self(WGS84_B * a * (sigma - delta_sigma));
+            }
+        }
+
+        Self::haversine(lon1, lat1, lon2, lat2)
+    }
+
+    fn haversine(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> Distance {
+        const MEAN_RADIUS: f64 = (2.0 * WGS84_A + WGS84_B) / 3.0;
+        let h = ((lat2 - lat1) / 2.0).sin().powi(2)
+            + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+        Self(2.0 * MEAN_RADIUS * h.sqrt().min(1.0).asin())
+    }
+
+    pub fn is_within(&self, radius: &Radius) -> bool {
+        self.0 <= f64::from(*radius.as_ref())
+    }
+}
+
+impl AsRef<f64> for Distance {
+    fn as_ref(&self) -> &f64 {
+        &self.0
+    }
+}
+
+impl From<Distance> for f64 {
+    fn from(value: Distance) -> Self {
+        value.0
+    }
+}
+
+impl Display for Distance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.1}m", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vincenty_test() -> anyhow::Result<()> {
+        // Flinders Peak -> Buninyong, the reference pair of Vincenty (1975).
+        let flinders = Position::new(144.424_867_888_9f64, -37.951_033_416_7f64)?;
+        let buninyong = Position::new(143.926_495_527_8f64, -37.652_821_138_9f64)?;
+        let d = Distance::between(&flinders, &buninyong);
+        assert!((f64::from(d) - 54_972.271).abs() < 0.01, "{d}");
+
+        let same = Distance::between(&flinders, &flinders);
+        assert_eq!(f64::from(same), 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn antipodal_fallback_test() -> anyhow::Result<()> {
+        let from = Position::new(0f64, 0f64)?;
+        let to = Position::new(179.7f64, 0.5f64)?;
+        let d = Distance::between(&from, &to);
+        assert!(f64::from(d) > 19_900_000.0 && f64::from(d) < 20_050_000.0, "{d}");
+        Ok(())
+    }
+
+    #[test]
+    fn within_test() -> anyhow::Result<()> {
+        let office = Position::new(132.766_617_100_128_77f64, 33.841_405_349_477_995f64)?;
+        let near = Position::new(132.767_1f64, 33.841_8f64)?;
+        let far = Position::new(132.776_6f64, 33.841_4f64)?;
+        assert!(Distance::between(&office, &near).is_within(&Radius::new(100)));
+        assert!(!Distance::between(&office, &far).is_within(&Radius::new(100)));
+        Ok(())
+    }
+}
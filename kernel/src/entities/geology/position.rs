@@ -2,7 +2,7 @@ use crate::error::KernelError;
 use destructure::Destructure;
 use serde::{Deserialize, Serialize};
 
-use super::{Latitude, Longitude};
+use super::{Distance, Latitude, Longitude};
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, Destructure)]
 pub struct Position {
@@ -28,6 +28,10 @@ impl Position {
     pub fn y(&self) -> &Latitude {
         &self.y
     }
+
+    pub fn distance(&self, other: &Position) -> Distance {
+        Distance::between(self, other)
+    }
 }
 
 impl From<Position> for geo_types::Geometry {
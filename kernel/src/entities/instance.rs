@@ -1,33 +1,46 @@
+mod allocation;
+mod capacity;
+mod expiry;
+mod filter;
 mod finished_at;
 mod instance_id;
 mod ring_set;
 mod started_at;
+mod version;
 
-pub use self::{finished_at::*, instance_id::*, ring_set::*, started_at::*};
+pub use self::{allocation::*, capacity::*, expiry::*, filter::*, finished_at::*, instance_id::*, ring_set::*, started_at::*, version::*};
 use std::cmp::Ordering;
+use time::OffsetDateTime;
 use destructure::Destructure;
 use serde::{Deserialize, Serialize};
+use crate::entities::location::LocationId;
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize, Destructure)]
+#[derive(Debug, Clone, Deserialize, Serialize, Destructure)]
 pub struct Instance {
     id: InstanceId,
+    location: LocationId,
     rings: RingSet,
     started_at: StartedAt,
     finished_at: FinishedAt,
+    version: Version,
 }
 
 impl Instance {
     pub fn new(
         id: InstanceId,
+        location: LocationId,
         rings: RingSet,
         started_at: StartedAt,
         finished_at: FinishedAt,
+        version: Version,
     ) -> Instance {
         Self {
             id,
+            location,
             rings,
             started_at,
             finished_at,
+            version,
         }
     }
 }
@@ -37,6 +50,10 @@ impl Instance {
         &self.id
     }
 
+    pub fn location(&self) -> &LocationId {
+        &self.location
+    }
+
     pub fn rings(&self) -> &RingSet {
         &self.rings
     }
@@ -48,6 +65,18 @@ impl Instance {
     pub fn finished_at(&self) -> &FinishedAt {
         &self.finished_at
     }
+
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    /// When the last ring was placed, or when the instance started if it has no rings yet.
+    pub fn last_active_at(&self) -> OffsetDateTime {
+        self.rings
+            .iter()
+            .map(|ring| *ring.created_at().as_ref())
+            .fold(*self.started_at.as_ref(), OffsetDateTime::max)
+    }
 }
 
 impl Eq for Instance {}
@@ -66,6 +95,6 @@ impl Ord for Instance {
 
 impl PartialOrd<Self> for Instance {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.started_at.partial_cmp(&other.started_at)
+        Some(self.cmp(other))
     }
 }
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// How the index of a ring is chosen, when the client does not request a specific one.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Allocation {
+    /// The smallest index not yet used in the instance.
+    #[default]
+    Next,
+    /// Any of the indexes not yet used in the instance.
+    Random,
+}
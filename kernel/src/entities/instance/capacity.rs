@@ -0,0 +1,61 @@
+use crate::entities::ring::Index;
+use crate::error::KernelError;
+use serde::{Deserialize, Serialize};
+
+/// Number of rings an instance accepts before it is finished.
+///
+/// It is defined per location, and copied to the instance when it starts.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "i32", into = "i32")]
+pub struct Capacity(i32);
+
+impl Capacity {
+    pub const MAX: i32 = 1000;
+
+    pub fn new(capacity: impl Into<i32>) -> Result<Capacity, KernelError> {
+        let capacity = capacity.into();
+        if !(1..=Self::MAX).contains(&capacity) {
+            return Err(KernelError::Validation {
+                msg: "capacity value should be 1~1000",
+            });
+        }
+        Ok(Self(capacity))
+    }
+
+    /// Whether `index` is a valid slot under this capacity. (`0..capacity`)
+    pub fn contains(&self, index: &Index) -> bool {
+        (0..self.0).contains(index.as_ref())
+    }
+}
+
+impl AsRef<i32> for Capacity {
+    fn as_ref(&self) -> &i32 {
+        &self.0
+    }
+}
+
+impl From<Capacity> for i32 {
+    fn from(value: Capacity) -> Self {
+        value.0
+    }
+}
+
+impl From<Capacity> for usize {
+    fn from(value: Capacity) -> Self {
+        // Why `as`?: Because `Capacity` is guaranteed to be positive on construction.
+        value.0 as usize
+    }
+}
+
+impl TryFrom<i32> for Capacity {
+    type Error = KernelError;
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl Default for Capacity {
+    fn default() -> Self {
+        Self(70)
+    }
+}
@@ -0,0 +1,102 @@
+use crate::entities::instance::Instance;
+use time::{Duration, OffsetDateTime};
+
+/// Conditions under which an unfinished instance is closed before it is filled.
+///
+/// An instance without rings never expires, since rolling it over would change nothing.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Expiry {
+    max_age: Option<Duration>,
+    idle: Option<Duration>,
+}
+
+impl Expiry {
+    /// + `max_age` - Time since the instance has started.
+    /// + `idle`    - Time since the last ring was placed.
+    pub fn new(max_age: impl Into<Option<Duration>>, idle: impl Into<Option<Duration>>) -> Expiry {
+        Self {
+            max_age: max_age.into(),
+            idle: idle.into(),
+        }
+    }
+
+    pub fn max_age(&self) -> &Option<Duration> {
+        &self.max_age
+    }
+
+    pub fn idle(&self) -> &Option<Duration> {
+        &self.idle
+    }
+
+    pub fn is_expired(&self, instance: &Instance, now: OffsetDateTime) -> bool {
+        if instance.finished_at().is_finished() || instance.rings().is_empty() {
+            return false;
+        }
+
+        let aged = self
+            .max_age
+            .is_some_and(|max_age| now - *instance.started_at().as_ref() >= max_age);
+        let idled = self
+            .idle
+            .is_some_and(|idle| now - instance.last_active_at() >= idle);
+
+        aged || idled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::geology::Position;
+    use crate::entities::instance::{Capacity, FinishedAt, InstanceId, RingSet, StartedAt, Version};
+    use crate::entities::location::LocationId;
+    use crate::entities::ring::{CreatedAt, HiddenAt, HueColor, Index, Ring, RingId, UserId};
+
+    fn instance(started_at: OffsetDateTime, placed_at: &[OffsetDateTime]) -> anyhow::Result<Instance> {
+        let rings = placed_at
+            .iter()
+            .enumerate()
+            .map(|(index, at)| -> anyhow::Result<Ring> {
+                Ok(Ring::new(
+                    RingId::default(),
+                    Position::new(132.7666f64, 33.8414f64)?,
+                    LocationId::default(),
+                    UserId::default(),
+                    Index::new(index as i32)?,
+                    HueColor::new(0),
+                    CreatedAt::new(*at),
+                    HiddenAt::default(),
+                ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Instance::new(
+            InstanceId::default(),
+            LocationId::default(),
+            RingSet::new(Capacity::default(), rings)?,
+            StartedAt::new(started_at),
+            FinishedAt::default(),
+            Version::default(),
+        ))
+    }
+
+    #[test]
+    fn expiry_test() -> anyhow::Result<()> {
+        let now = OffsetDateTime::now_utc();
+        let expiry = Expiry::new(Duration::days(7), Duration::hours(6));
+
+        let fresh = instance(now - Duration::hours(1), &[now - Duration::minutes(30)])?;
+        assert!(!expiry.is_expired(&fresh, now));
+
+        let idle = instance(now - Duration::days(1), &[now - Duration::hours(12)])?;
+        assert!(expiry.is_expired(&idle, now));
+
+        let aged = instance(now - Duration::days(8), &[now - Duration::minutes(1)])?;
+        assert!(expiry.is_expired(&aged, now));
+
+        let empty = instance(now - Duration::days(30), &[])?;
+        assert!(!expiry.is_expired(&empty, now));
+
+        assert!(!Expiry::default().is_expired(&aged, now));
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use crate::entities::location::LocationId;
+use crate::entities::ring::UserId;
+use crate::error::KernelError;
+use time::OffsetDateTime;
+
+/// A half-open time range `[since, until)`. Either end may be left open.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Period {
+    since: Option<OffsetDateTime>,
+    until: Option<OffsetDateTime>,
+}
+
+impl Period {
+    pub fn new(since: Option<OffsetDateTime>, until: Option<OffsetDateTime>) -> Result<Period, KernelError> {
+        if let (Some(since), Some(until)) = (since, until) {
+            if since > until {
+                return Err(KernelError::Validation {
+                    msg: "the start of a period should not be after the end.",
+                });
+            }
+        }
+        Ok(Self { since, until })
+    }
+
+    pub fn since(&self) -> &Option<OffsetDateTime> {
+        &self.since
+    }
+
+    pub fn until(&self) -> &Option<OffsetDateTime> {
+        &self.until
+    }
+}
+
+/// Conditions to narrow down instances in a listing. Unset conditions match everything.
+#[derive(Debug, Clone, Default)]
+pub struct InstanceFilter {
+    location: Option<LocationId>,
+    user: Option<UserId>,
+    started: Period,
+    finished: Period,
+    is_finished: Option<bool>,
+}
+
+impl InstanceFilter {
+    pub fn new(
+        location: Option<LocationId>,
+        user: Option<UserId>,
+        started: Period,
+        finished: Period,
+        is_finished: Option<bool>,
+    ) -> InstanceFilter {
+        Self {
+            location,
+            user,
+            started,
+            finished,
+            is_finished,
+        }
+    }
+
+    pub fn location(&self) -> &Option<LocationId> {
+        &self.location
+    }
+
+    /// Only instances containing a ring placed by the user.
+    pub fn user(&self) -> &Option<UserId> {
+        &self.user
+    }
+
+    pub fn started(&self) -> &Period {
+        &self.started
+    }
+
+    pub fn finished(&self) -> &Period {
+        &self.finished
+    }
+
+    /// `Some(true)` for finished instances only, `Some(false)` for open ones only.
+    pub fn is_finished(&self) -> &Option<bool> {
+        &self.is_finished
+    }
+}
@@ -1,37 +1,50 @@
-use crate::entities::ring::Ring;
+use crate::entities::instance::{Allocation, Capacity};
+use crate::entities::ring::{Index, Ring};
 use crate::error::KernelError;
+use rand::seq::IteratorRandom;
 use serde::{Deserialize, Serialize};
 use std::collections::btree_set::Iter;
 use std::collections::BTreeSet;
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
-pub struct RingSet(BTreeSet<Ring>);
+pub struct RingSet {
+    capacity: Capacity,
+    rings: BTreeSet<Ring>,
+}
 
 impl RingSet {
-    pub fn new(vec: impl Into<Vec<Ring>>) -> Result<RingSet, KernelError> {
+    pub fn new(capacity: Capacity, vec: impl Into<Vec<Ring>>) -> Result<RingSet, KernelError> {
         let rings = vec.into();
-        if (rings.len() + 1) > 71 {
+        if rings.len() > usize::from(capacity) {
             return Err(KernelError::Validation {
-                msg: "`RingSet` length should be less than 71.",
+                msg: "`RingSet` length should be less than or equal to the capacity.",
             });
         }
 
-        Ok(Self(BTreeSet::from_iter(rings)))
+        Ok(Self {
+            capacity,
+            rings: BTreeSet::from_iter(rings),
+        })
     }
 
     pub fn add(&mut self, ring: Ring) -> Result<(), KernelError> {
-        if (self.0.len() + 1) >= 71 {
+        if self.is_filled() {
             return Err(KernelError::Validation {
-                msg: "`RingSet` length should be less than 71.",
+                msg: "`RingSet` length should be less than or equal to the capacity.",
             });
         }
-        if self.0.iter().any(|item| item.indexed() == ring.indexed()) {
+        if !self.capacity.contains(ring.indexed()) {
+            return Err(KernelError::Validation {
+                msg: "`Index` should be less than the capacity.",
+            });
+        }
+        if self.rings.iter().any(|item| item.indexed() == ring.indexed()) {
             return Err(KernelError::Conflict {
                 entity: "ring",
                 msg: "`Index` should be Unique within a defined value.",
             });
         }
-        if let Some(last) = self.0.last() {
+        if let Some(last) = self.rings.last() {
             if last.user() == ring.user() {
                 return Err(KernelError::Conflict {
                     entity: "ring",
@@ -39,7 +52,7 @@ impl RingSet {
                 });
             }
         }
-        if !self.0.insert(ring) {
+        if !self.rings.insert(ring) {
             return Err(KernelError::Conflict {
                 entity: "ring",
                 msg:
@@ -49,26 +62,41 @@ impl RingSet {
         Ok(())
     }
 
+    /// Pick an index that is not yet used, following `allocation`.
+    pub fn allocate(&self, allocation: Allocation) -> Result<Index, KernelError> {
+        let mut free = (0..*self.capacity.as_ref())
+            .filter(|index| self.rings.iter().all(|ring| ring.indexed().as_ref() != index));
+        let index = match allocation {
+            Allocation::Next => free.next(),
+            Allocation::Random => free.choose(&mut rand::thread_rng()),
+        };
+        let Some(index) = index else {
+            return Err(KernelError::Conflict {
+                entity: "ring",
+                msg: "There is no free `Index` left in the instance.",
+            });
+        };
+        Index::new(index)
+    }
+
+    pub fn capacity(&self) -> &Capacity {
+        &self.capacity
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.rings.len() >= usize::from(self.capacity)
+    }
+
     pub fn iter(&self) -> Iter<'_, Ring> {
-        self.0.iter()
+        self.rings.iter()
     }
 
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.rings.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
-    }
-}
-
-impl FromIterator<Ring> for Result<RingSet, KernelError> {
-    fn from_iter<T: IntoIterator<Item = Ring>>(iter: T) -> Self {
-        let mut rings: Vec<Ring> = Vec::new();
-        for ring in iter {
-            rings.push(ring);
-        }
-        RingSet::new(rings)
+        self.rings.is_empty()
     }
 }
 
@@ -77,18 +105,76 @@ impl IntoIterator for RingSet {
     type IntoIter = std::collections::btree_set::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.rings.into_iter()
     }
 }
 
 impl AsRef<BTreeSet<Ring>> for RingSet {
     fn as_ref(&self) -> &BTreeSet<Ring> {
-        &self.0
+        &self.rings
     }
 }
 
 impl From<RingSet> for BTreeSet<Ring> {
     fn from(value: RingSet) -> Self {
-        value.0
+        value.rings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::geology::Position;
+    use crate::entities::location::LocationId;
+    use crate::entities::ring::{CreatedAt, HiddenAt, HueColor, Index, RingId, UserId};
+    use time::{Duration, OffsetDateTime};
+
+    fn ring(index: i32, offset: i64) -> anyhow::Result<Ring> {
+        Ok(Ring::new(
+            RingId::default(),
+            Position::new(132.7666f64, 33.8414f64)?,
+            LocationId::default(),
+            UserId::default(),
+            Index::new(index)?,
+            HueColor::new(index),
+            CreatedAt::new(OffsetDateTime::now_utc() + Duration::seconds(offset)),
+            HiddenAt::default(),
+        ))
+    }
+
+    #[test]
+    fn capacity_test() -> anyhow::Result<()> {
+        let mut set = RingSet::new(Capacity::new(3)?, Vec::new())?;
+
+        assert!(set.add(ring(3, 0)?).is_err());
+
+        for index in 0..3 {
+            set.add(ring(index, index.into())?)?;
+        }
+        assert!(set.is_filled());
+        assert!(set.add(ring(1, 10)?).is_err());
+
+        let over = (0..4).map(|index| ring(index, index.into())).collect::<Result<Vec<_>, _>>()?;
+        assert!(RingSet::new(Capacity::new(3)?, over).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn allocate_test() -> anyhow::Result<()> {
+        let mut set = RingSet::new(Capacity::new(4)?, Vec::new())?;
+        set.add(ring(0, 0)?)?;
+        set.add(ring(2, 1)?)?;
+
+        assert_eq!(set.allocate(Allocation::Next)?, Index::new(1)?);
+        for _ in 0..16 {
+            let index = set.allocate(Allocation::Random)?;
+            assert!(index == Index::new(1)? || index == Index::new(3)?);
+        }
+
+        set.add(ring(1, 2)?)?;
+        set.add(ring(3, 3)?)?;
+        assert!(set.allocate(Allocation::Next).is_err());
+        assert!(set.allocate(Allocation::Random).is_err());
+        Ok(())
     }
 }
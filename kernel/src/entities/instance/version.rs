@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// Revision of an instance used for optimistic locking.
+///
+/// It is incremented each time the instance is stored,
+/// and a store based on an outdated revision is rejected as a conflict.
+#[derive(Debug, Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Version(i64);
+
+impl Version {
+    pub fn new(version: impl Into<i64>) -> Version {
+        Self(version.into())
+    }
+
+    pub fn next(self) -> Version {
+        Self(self.0 + 1)
+    }
+}
+
+impl AsRef<i64> for Version {
+    fn as_ref(&self) -> &i64 {
+        &self.0
+    }
+}
+
+impl From<Version> for i64 {
+    fn from(value: Version) -> Self {
+        value.0
+    }
+}
@@ -5,7 +5,8 @@ mod location_id;
 
 pub use self::{localize::*, localize_id::*, localized_name::*, location_id::*};
 
-use crate::entities::geology::Radius;
+use crate::entities::geology::{Distance, Radius};
+use crate::entities::instance::Capacity;
 use destructure::Destructure;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -19,15 +20,23 @@ pub struct Location {
     id: LocationId,
     pos: Position,
     rad: Radius,
+    capacity: Capacity,
     localize: Vec<Localize>,
 }
 
 impl Location {
-    pub fn new(id: LocationId, pos: Position, rad: Radius, localize: Vec<Localize>) -> Location {
+    pub fn new(
+        id: LocationId,
+        pos: Position,
+        rad: Radius,
+        capacity: Capacity,
+        localize: Vec<Localize>,
+    ) -> Location {
         Self {
             id,
             pos,
             rad,
+            capacity,
             localize,
         }
     }
@@ -36,12 +45,14 @@ impl Location {
         id: impl Into<Uuid>,
         pos: impl TryInto<Position, Error = KernelError>,
         rad: impl Into<i32>,
+        capacity: impl Into<i32>,
         localize: impl Into<Vec<Localize>>,
     ) -> Result<Location, KernelError> {
         Ok(Self {
             id: LocationId::new(id),
             pos: pos.try_into()?,
             rad: Radius::new(rad),
+            capacity: Capacity::new(capacity)?,
             localize: localize.into(),
         })
     }
@@ -58,9 +69,29 @@ impl Location {
         &self.rad
     }
 
+    pub fn capacity(&self) -> &Capacity {
+        &self.capacity
+    }
+
     pub fn localize(&self) -> &[Localize] {
         &self.localize
     }
+
+    pub fn distance(&self, pos: &Position) -> Distance {
+        self.pos.distance(pos)
+    }
+
+    /// Verify that `pos` lies inside the circle described by this location's position and radius.
+    pub fn verify_within(&self, pos: &Position) -> Result<(), KernelError> {
+        let distance = self.distance(pos);
+        if !distance.is_within(&self.rad) {
+            return Err(KernelError::OutOfRange {
+                distance,
+                radius: self.rad.into(),
+            });
+        }
+        Ok(())
+    }
 }
 
 impl TryFrom<Location> for geojson::Feature {
@@ -68,9 +99,6 @@ impl TryFrom<Location> for geojson::Feature {
     fn try_from(value: Location) -> Result<Self, Self::Error> {
         use serde_json::{Map, Value};
 
-        #[derive(serde::Serialize)]
-        struct LocalizeExt(Map<String, Value>);
-
         let loc = value
             .localize
             .into_iter()
@@ -84,6 +112,7 @@ impl TryFrom<Location> for geojson::Feature {
 
         let mut obj = geojson::JsonObject::new();
         obj.insert("radius".to_string(), i32::from(value.rad).into());
+        obj.insert("capacity".to_string(), i32::from(value.capacity).into());
         obj.insert("localize".to_string(), Value::from(map));
 
         Ok(geojson::Feature {
@@ -133,6 +162,8 @@ impl TryFrom<geojson::GeoJson> for Location {
                 #[derive(Deserialize)]
                 pub struct Ext {
                     radius: Radius,
+                    #[serde(default)]
+                    capacity: Capacity,
                     localize: Vec<Localize>,
                 }
 
@@ -148,7 +179,7 @@ impl TryFrom<geojson::GeoJson> for Location {
                         msg: "`properties` does not empty value. This value must be required.",
                     })?;
 
-                let loc = Location::new(lid, pos, props.radius, props.localize);
+                let loc = Location::new(lid, pos, props.radius, props.capacity, props.localize);
                 Ok(loc)
             }
             geojson::GeoJson::FeatureCollection(_) => Err(KernelError::UnSupportedTypeConversion {
@@ -166,6 +197,7 @@ impl TryFrom<geojson::GeoJson> for Location {
 #[cfg(test)]
 mod tests {
     use crate::entities::geology::{Position, Radius};
+    use crate::entities::instance::Capacity;
     use crate::entities::location::{Localize, Location, LocationId};
     use geojson::Feature;
 
@@ -175,6 +207,7 @@ mod tests {
             LocationId::default(),
             Position::new(135.315684651, 64.126213518)?,
             Radius::new(100),
+            Capacity::default(),
             vec![Localize::new("jp", "あいうえお")?],
         );
 
@@ -184,4 +217,25 @@ mod tests {
         println!("{:#?}", geos);
         Ok(())
     }
+
+    #[test]
+    fn verify_within_test() -> anyhow::Result<()> {
+        let loc = Location::new(
+            LocationId::default(),
+            Position::new(132.76661710012877f64, 33.841405349477995f64)?,
+            Radius::new(100),
+            Capacity::default(),
+            vec![Localize::new("jp", "愛媛県庁第一別館")?],
+        );
+
+        let near = Position::new(132.7671f64, 33.8418f64)?;
+        assert!(loc.verify_within(&near).is_ok());
+
+        let far = Position::new(132.7766f64, 33.8414f64)?;
+        assert!(matches!(
+            loc.verify_within(&far),
+            Err(crate::error::KernelError::OutOfRange { radius: 100, .. })
+        ));
+        Ok(())
+    }
 }
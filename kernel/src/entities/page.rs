@@ -0,0 +1,69 @@
+mod cursor;
+mod limit;
+
+pub use self::{cursor::*, limit::*};
+
+/// A slice of a listing, and where the following one starts.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    items: Vec<T>,
+    next: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: impl Into<Vec<T>>, next: impl Into<Option<Cursor>>) -> Page<T> {
+        Self {
+            items: items.into(),
+            next: next.into(),
+        }
+    }
+
+    /// Build a page from up to `limit + 1` items fetched in order.
+    ///
+    /// The surplus item only tells that there is a following page,
+    /// which then starts after the last item kept.
+    pub fn from_surplus(mut items: Vec<T>, limit: &Limit, cursor: impl Fn(&T) -> Cursor) -> Page<T> {
+        let limit = usize::from(*limit);
+        let next = if items.len() > limit {
+            items.truncate(limit);
+            items.last().map(cursor)
+        } else {
+            None
+        };
+        Self { items, next }
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn next(&self) -> Option<&Cursor> {
+        self.next.as_ref()
+    }
+
+    pub fn into_parts(self) -> (Vec<T>, Option<Cursor>) {
+        (self.items, self.next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::OffsetDateTime;
+    use uuid::Uuid;
+
+    #[test]
+    fn surplus_test() -> anyhow::Result<()> {
+        let at = OffsetDateTime::now_utc();
+        let items = (0..4).map(|_| Uuid::new_v4()).collect::<Vec<_>>();
+
+        let page = Page::from_surplus(items.clone(), &Limit::new(3)?, |id| Cursor::new(at, *id));
+        assert_eq!(page.items(), &items[..3]);
+        assert_eq!(page.next(), Some(&Cursor::new(at, items[2])));
+
+        let page = Page::from_surplus(items.clone(), &Limit::new(4)?, |id| Cursor::new(at, *id));
+        assert_eq!(page.items().len(), 4);
+        assert!(page.next().is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use crate::error::KernelError;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use std::fmt::{Display, Formatter};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Position in a listing ordered by time and then id, newest first.
+///
+/// It is handed to clients as an opaque string, see [`Cursor::encode`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cursor {
+    at: OffsetDateTime,
+    id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(at: impl Into<OffsetDateTime>, id: impl Into<Uuid>) -> Cursor {
+        Self {
+            at: at.into(),
+            id: id.into(),
+        }
+    }
+
+    pub fn at(&self) -> &OffsetDateTime {
+        &self.at
+    }
+
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn encode(&self) -> String {
+        let mut bytes = [0u8; 32];
+        bytes[..16].copy_from_slice(&self.at.unix_timestamp_nanos().to_be_bytes());
+        bytes[16..].copy_from_slice(self.id.as_bytes());
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    pub fn decode(encoded: impl AsRef<str>) -> Result<Cursor, KernelError> {
+        let invalid = |msg: anyhow::Error| KernelError::InvalidFormat { ty: "Cursor", msg };
+
+        let bytes = URL_SAFE_NO_PAD
+            .decode(encoded.as_ref())
+            .map_err(|e| invalid(anyhow::Error::new(e)))?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| invalid(anyhow::anyhow!("cursor should be 32 bytes.")))?;
+
+        let mut nanos = [0u8; 16];
+        nanos.copy_from_slice(&bytes[..16]);
+        let at = OffsetDateTime::from_unix_timestamp_nanos(i128::from_be_bytes(nanos))
+            .map_err(|e| invalid(anyhow::Error::new(e)))?;
+        let id = Uuid::from_slice(&bytes[16..]).map_err(|e| invalid(anyhow::Error::new(e)))?;
+
+        Ok(Self { at, id })
+    }
+}
+
+impl Display for Cursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_test() -> anyhow::Result<()> {
+        let cursor = Cursor::new(OffsetDateTime::now_utc(), Uuid::new_v4());
+        assert_eq!(Cursor::decode(cursor.encode())?, cursor);
+
+        assert!(Cursor::decode("not a cursor").is_err());
+        assert!(Cursor::decode(URL_SAFE_NO_PAD.encode([0u8; 8])).is_err());
+        Ok(())
+    }
+}
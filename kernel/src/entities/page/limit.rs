@@ -0,0 +1,45 @@
+use crate::error::KernelError;
+use serde::{Deserialize, Serialize};
+
+/// Number of items in a page.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Limit(i64);
+
+impl Limit {
+    pub const MAX: i64 = 100;
+
+    pub fn new(limit: impl Into<i64>) -> Result<Limit, KernelError> {
+        let limit = limit.into();
+        if !(1..=Self::MAX).contains(&limit) {
+            return Err(KernelError::Validation {
+                msg: "limit value should be 1~100",
+            });
+        }
+        Ok(Self(limit))
+    }
+}
+
+impl AsRef<i64> for Limit {
+    fn as_ref(&self) -> &i64 {
+        &self.0
+    }
+}
+
+impl From<Limit> for i64 {
+    fn from(value: Limit) -> Self {
+        value.0
+    }
+}
+
+impl From<Limit> for usize {
+    fn from(value: Limit) -> Self {
+        // Why `as`?: Because `Limit` is guaranteed to be positive on construction.
+        value.0 as usize
+    }
+}
+
+impl Default for Limit {
+    fn default() -> Self {
+        Self(20)
+    }
+}
@@ -1,10 +1,12 @@
 mod created_at;
+mod hidden_at;
 mod hue;
 mod index;
+mod record;
 mod ring_id;
 mod user_id;
 
-pub use self::{created_at::*, hue::*, index::*, ring_id::*, user_id::*};
+pub use self::{created_at::*, hidden_at::*, hue::*, index::*, record::*, ring_id::*, user_id::*};
 
 use crate::entities::geology::Position;
 use crate::entities::location::LocationId;
@@ -21,9 +23,17 @@ pub struct Ring {
     indexed: Index,
     hue: HueColor,
     created_at: CreatedAt,
+    /// Only present once hidden, which keeps it in the audit log of moderation.
+    #[serde(default, skip_serializing_if = "is_visible")]
+    hidden_at: HiddenAt,
+}
+
+fn is_visible(hidden_at: &HiddenAt) -> bool {
+    !hidden_at.is_hidden()
 }
 
 impl Ring {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: RingId,
         pos_in: Position,
@@ -32,6 +42,7 @@ impl Ring {
         indexed: Index,
         hue: HueColor,
         created_at: CreatedAt,
+        hidden_at: HiddenAt,
     ) -> Ring {
         Self {
             id,
@@ -41,6 +52,7 @@ impl Ring {
             indexed,
             hue,
             created_at,
+            hidden_at,
         }
     }
 }
@@ -73,6 +85,10 @@ impl Ring {
     pub fn created_at(&self) -> &CreatedAt {
         &self.created_at
     }
+
+    pub fn hidden_at(&self) -> &HiddenAt {
+        &self.hidden_at
+    }
 }
 
 impl Eq for Ring {}
@@ -109,6 +125,6 @@ impl Ord for Ring {
 
 impl PartialOrd<Self> for Ring {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.created_at.partial_cmp(&other.created_at)
+        Some(self.cmp(other))
     }
 }
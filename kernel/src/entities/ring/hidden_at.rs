@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// When the ring was hidden by moderation. `None` while it is visible.
+#[derive(Debug, Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Deserialize, Serialize)]
+pub struct HiddenAt(#[serde(with = "time::serde::iso8601::option")] Option<OffsetDateTime>);
+
+impl HiddenAt {
+    pub fn new<T>(at: impl Into<Option<T>>) -> HiddenAt
+    where
+        T: Into<OffsetDateTime>,
+    {
+        Self(at.into().map(Into::into))
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+impl AsRef<Option<OffsetDateTime>> for HiddenAt {
+    fn as_ref(&self) -> &Option<OffsetDateTime> {
+        &self.0
+    }
+}
+
+impl From<HiddenAt> for Option<OffsetDateTime> {
+    fn from(value: HiddenAt) -> Self {
+        value.0
+    }
+}
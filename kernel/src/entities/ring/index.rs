@@ -6,11 +6,14 @@ use crate::entities::image::AsTraitType;
 pub struct Index(i32);
 
 impl Index {
+    /// Only the lower bound is checked here, as the upper one depends on the location.
+    ///
+    /// [`RingSet::add`](crate::entities::instance::RingSet::add) checks it against the capacity of the location.
     pub fn new(index: impl Into<i32>) -> Result<Index, KernelError> {
         let index = index.into();
-        if index < 0 && index > 69 {
+        if index < 0 {
             return Err(KernelError::Validation {
-                msg: "index value should be 0~69",
+                msg: "index value should not be negative",
             });
         }
         Ok(Self(index))
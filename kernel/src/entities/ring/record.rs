@@ -0,0 +1,41 @@
+use crate::entities::instance::InstanceId;
+use crate::entities::location::Localize;
+use crate::entities::ring::Ring;
+use destructure::Destructure;
+
+/// A ring along with where it was placed, as listed in the history of a user.
+#[derive(Debug, Clone, Destructure)]
+pub struct RingRecord {
+    ring: Ring,
+    instance: InstanceId,
+    localize: Vec<Localize>,
+    has_image: bool,
+}
+
+impl RingRecord {
+    pub fn new(ring: Ring, instance: InstanceId, localize: Vec<Localize>, has_image: bool) -> RingRecord {
+        Self {
+            ring,
+            instance,
+            localize,
+            has_image,
+        }
+    }
+
+    pub fn ring(&self) -> &Ring {
+        &self.ring
+    }
+
+    pub fn instance(&self) -> &InstanceId {
+        &self.instance
+    }
+
+    /// Localized names of the location the ring was placed at.
+    pub fn localize(&self) -> &[Localize] {
+        &self.localize
+    }
+
+    pub fn has_image(&self) -> bool {
+        self.has_image
+    }
+}
@@ -1,3 +1,7 @@
 mod admin;
+mod admin_key;
+mod device;
 
 pub use self::admin::*;
+pub use self::admin_key::*;
+pub use self::device::*;
@@ -0,0 +1,289 @@
+use crate::entities::token::AdminToken;
+use crate::error::KernelError;
+use destructure::Destructure;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct AdminKeyId(Uuid);
+
+impl AdminKeyId {
+    pub fn new(id: impl Into<Uuid>) -> AdminKeyId {
+        Self(id.into())
+    }
+}
+
+impl AsRef<Uuid> for AdminKeyId {
+    fn as_ref(&self) -> &Uuid {
+        &self.0
+    }
+}
+
+impl From<AdminKeyId> for Uuid {
+    fn from(value: AdminKeyId) -> Self {
+        value.0
+    }
+}
+
+impl Default for AdminKeyId {
+    fn default() -> Self {
+        Self::new(Uuid::new_v4())
+    }
+}
+
+impl Display for AdminKeyId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// Label of an [`AdminKey`], telling who or what holds it.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct KeyName(String);
+
+impl KeyName {
+    pub fn new(name: impl Into<String>) -> Result<KeyName, KernelError> {
+        let name = name.into();
+        if name.trim().is_empty() || name.chars().count() > 64 {
+            return Err(KernelError::Validation {
+                msg: "key name should be 1~64 characters.",
+            });
+        }
+        Ok(Self(name))
+    }
+}
+
+impl AsRef<str> for KeyName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<KeyName> for String {
+    fn from(value: KeyName) -> Self {
+        value.0
+    }
+}
+
+/// SHA-256 of an [`AdminToken`], which is all that is stored of it.
+///
+/// A plain hash is enough, as tokens are long random strings rather than passwords.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct KeyDigest(String);
+
+impl KeyDigest {
+    pub fn of(token: &AdminToken) -> KeyDigest {
+        let digest = Sha256::digest(token.as_ref().as_bytes());
+        Self(format!("{digest:x}"))
+    }
+
+    pub fn unchecked_new(digest: impl Into<String>) -> KeyDigest {
+        Self(digest.into())
+    }
+}
+
+impl AsRef<str> for KeyDigest {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// What an [`AdminKey`] is allowed to do.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Scope {
+    /// Every admin API, including the ones added later. (`*`)
+    All,
+    /// Create, update and delete locations. (`locations:write`)
+    LocationsWrite,
+    /// Hide, restore and delete rings. (`rings:moderate`)
+    RingsModerate,
+    /// Read the audit log of admin mutations. (`audit:read`)
+    AuditRead,
+}
+
+impl Scope {
+    pub const VARIANTS: [Scope; 4] = [
+        Scope::All,
+        Scope::LocationsWrite,
+        Scope::RingsModerate,
+        Scope::AuditRead,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::All => "*",
+            Scope::LocationsWrite => "locations:write",
+            Scope::RingsModerate => "rings:moderate",
+            Scope::AuditRead => "audit:read",
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = KernelError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::VARIANTS.into_iter().find(|scope| scope.as_str() == s) {
+            Some(scope) => Ok(scope),
+            None => Err(KernelError::Validation {
+                msg: "unknown scope.",
+            }),
+        }
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Credential for the admin APIs, stored without the token itself.
+#[derive(Debug, Clone, Destructure)]
+pub struct AdminKey {
+    id: AdminKeyId,
+    name: KeyName,
+    digest: KeyDigest,
+    scopes: BTreeSet<Scope>,
+    created_at: OffsetDateTime,
+    expires_at: Option<OffsetDateTime>,
+    last_used_at: Option<OffsetDateTime>,
+    revoked_at: Option<OffsetDateTime>,
+}
+
+impl AdminKey {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: AdminKeyId,
+        name: KeyName,
+        digest: KeyDigest,
+        scopes: BTreeSet<Scope>,
+        created_at: OffsetDateTime,
+        expires_at: Option<OffsetDateTime>,
+        last_used_at: Option<OffsetDateTime>,
+        revoked_at: Option<OffsetDateTime>,
+    ) -> AdminKey {
+        Self {
+            id,
+            name,
+            digest,
+            scopes,
+            created_at,
+            expires_at,
+            last_used_at,
+            revoked_at,
+        }
+    }
+
+    /// Create a key along with its token. The token can not be recovered from the key afterwards.
+    pub fn issue(
+        name: KeyName,
+        scopes: BTreeSet<Scope>,
+        expires_at: Option<OffsetDateTime>,
+    ) -> Result<(AdminKey, AdminToken), KernelError> {
+        if scopes.is_empty() {
+            return Err(KernelError::Validation {
+                msg: "a key should have at least one scope.",
+            });
+        }
+        let token = AdminToken::default();
+        let key = Self::new(
+            AdminKeyId::default(),
+            name,
+            KeyDigest::of(&token),
+            scopes,
+            OffsetDateTime::now_utc(),
+            expires_at,
+            None,
+            None,
+        );
+        Ok((key, token))
+    }
+}
+
+impl AdminKey {
+    pub fn id(&self) -> &AdminKeyId {
+        &self.id
+    }
+
+    pub fn name(&self) -> &KeyName {
+        &self.name
+    }
+
+    pub fn digest(&self) -> &KeyDigest {
+        &self.digest
+    }
+
+    pub fn scopes(&self) -> &BTreeSet<Scope> {
+        &self.scopes
+    }
+
+    pub fn created_at(&self) -> &OffsetDateTime {
+        &self.created_at
+    }
+
+    pub fn expires_at(&self) -> &Option<OffsetDateTime> {
+        &self.expires_at
+    }
+
+    pub fn last_used_at(&self) -> &Option<OffsetDateTime> {
+        &self.last_used_at
+    }
+
+    pub fn revoked_at(&self) -> &Option<OffsetDateTime> {
+        &self.revoked_at
+    }
+
+    /// Whether the key grants `scope`, regardless of being active.
+    pub fn allows(&self, scope: &Scope) -> bool {
+        self.scopes.contains(&Scope::All) || self.scopes.contains(scope)
+    }
+
+    /// Whether the key is neither revoked nor expired at `now`.
+    pub fn is_active(&self, now: OffsetDateTime) -> bool {
+        self.revoked_at.is_none() && !matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    #[test]
+    fn issue_test() -> anyhow::Result<()> {
+        let now = OffsetDateTime::now_utc();
+        let (key, token) = AdminKey::issue(
+            KeyName::new("venue")?,
+            BTreeSet::from([Scope::All]),
+            Some(now + Duration::days(1)),
+        )?;
+        assert_eq!(key.digest(), &KeyDigest::of(&token));
+        assert_ne!(key.digest().as_ref(), token.as_ref());
+        assert!(key.is_active(now));
+        assert!(!key.is_active(now + Duration::days(2)));
+
+        assert!(key.allows(&Scope::AuditRead));
+
+        assert!(AdminKey::issue(KeyName::new("venue")?, BTreeSet::new(), None).is_err());
+        assert!(KeyName::new(" ").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn scope_test() -> anyhow::Result<()> {
+        for scope in Scope::VARIANTS {
+            assert_eq!(scope.as_str().parse::<Scope>()?, scope);
+        }
+        assert!("locations:delete".parse::<Scope>().is_err());
+
+        let (key, _) = AdminKey::issue(KeyName::new("venue")?, BTreeSet::from([Scope::LocationsWrite]), None)?;
+        assert!(key.allows(&Scope::LocationsWrite));
+        assert!(!key.allows(&Scope::RingsModerate));
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// Signed identity of an anonymous device, issued by the server and sent back as a bearer token.
+///
+/// The [`UserId`](crate::entities::ring::UserId) of rings is taken from it rather than from requests.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct DeviceToken(String);
+
+impl DeviceToken {
+    pub fn new(token: impl Into<String>) -> DeviceToken {
+        Self(token.into())
+    }
+}
+
+impl AsRef<str> for DeviceToken {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<DeviceToken> for String {
+    fn from(value: DeviceToken) -> Self {
+        value.0
+    }
+}
@@ -1,3 +1,9 @@
 mod etag;
+mod event;
+mod presence;
+mod rate;
 
-pub use etag::*;
\ No newline at end of file
+pub use etag::*;
+pub use event::*;
+pub use presence::*;
+pub use rate::*;
@@ -0,0 +1,33 @@
+use crate::entities::location::LocationId;
+use destructure::Destructure;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Message for the clients watching `location`, fanned out to every server node.
+///
+/// `seq` is numbered by the [`EventBus`](crate::volatiles::EventBus) per location,
+/// so that every node agrees on the order.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Destructure)]
+pub struct Event {
+    location: LocationId,
+    seq: u64,
+    payload: Value,
+}
+
+impl Event {
+    pub fn new(location: LocationId, seq: u64, payload: Value) -> Event {
+        Self { location, seq, payload }
+    }
+
+    pub fn location(&self) -> &LocationId {
+        &self.location
+    }
+
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    pub fn payload(&self) -> &Value {
+        &self.payload
+    }
+}
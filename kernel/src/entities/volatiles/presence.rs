@@ -0,0 +1,79 @@
+use crate::entities::location::LocationId;
+use destructure::Destructure;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+/// A connected client counted by [`PresenceTracker`](crate::volatiles::PresenceTracker).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SessionId(Uuid);
+
+impl SessionId {
+    pub fn new(id: impl Into<Uuid>) -> SessionId {
+        Self(id.into())
+    }
+}
+
+impl AsRef<Uuid> for SessionId {
+    fn as_ref(&self) -> &Uuid {
+        &self.0
+    }
+}
+
+impl From<SessionId> for Uuid {
+    fn from(value: SessionId) -> Self {
+        value.0
+    }
+}
+
+impl Display for SessionId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// Number of sessions watching a location.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub struct Viewers(u64);
+
+impl Viewers {
+    pub fn new(count: impl Into<u64>) -> Viewers {
+        Self(count.into())
+    }
+}
+
+impl AsRef<u64> for Viewers {
+    fn as_ref(&self) -> &u64 {
+        &self.0
+    }
+}
+
+impl From<Viewers> for u64 {
+    fn from(value: Viewers) -> Self {
+        value.0
+    }
+}
+
+/// Number of viewers of `location` after it changed, fanned out to every server node.
+///
+/// Unlike an [`Event`](crate::entities::volatiles::Event) it is not numbered,
+/// since only the latest count matters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Destructure)]
+pub struct Presence {
+    location: LocationId,
+    viewers: Viewers,
+}
+
+impl Presence {
+    pub fn new(location: LocationId, viewers: Viewers) -> Presence {
+        Self { location, viewers }
+    }
+
+    pub fn location(&self) -> &LocationId {
+        &self.location
+    }
+
+    pub fn viewers(&self) -> Viewers {
+        self.viewers
+    }
+}
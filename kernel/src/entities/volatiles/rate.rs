@@ -0,0 +1,104 @@
+use crate::entities::ring::UserId;
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+use time::Duration;
+
+/// Who a [`RateLimit`] is counted for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RateKey(String);
+
+impl RateKey {
+    pub fn user(user: &UserId) -> RateKey {
+        Self(format!("user:{}", user.as_ref()))
+    }
+
+    pub fn addr(addr: &IpAddr) -> RateKey {
+        Self(format!("addr:{addr}"))
+    }
+
+    /// Devices registered from `addr`, counted apart from the rings of [`addr`](Self::addr).
+    pub fn device_addr(addr: &IpAddr) -> RateKey {
+        Self(format!("device_addr:{addr}"))
+    }
+}
+
+impl AsRef<str> for RateKey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for RateKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Token bucket holding up to `burst` tokens, regaining one every `refill`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    burst: u32,
+    refill: Duration,
+}
+
+impl RateLimit {
+    pub fn new(burst: u32, refill: Duration) -> RateLimit {
+        Self {
+            burst: burst.max(1),
+            refill,
+        }
+    }
+
+    /// `count` requests a minute, all of which can be made at once.
+    pub fn per_minute(count: u32) -> RateLimit {
+        let count = count.max(1);
+        Self::new(count, Duration::MINUTE / count)
+    }
+
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    pub fn refill(&self) -> Duration {
+        self.refill
+    }
+
+    /// Take a token from a bucket that held `tokens` `elapsed` ago.
+    ///
+    /// Returns the tokens left, or how long until one is available.
+    pub fn take(&self, tokens: f64, elapsed: Duration) -> Result<f64, Duration> {
+        let refilled = elapsed.as_seconds_f64() / self.refill.as_seconds_f64();
+        let tokens = (tokens + refilled).min(f64::from(self.burst));
+        if tokens >= 1.0 {
+            Ok(tokens - 1.0)
+        } else {
+            Err(self.refill * (1.0 - tokens))
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Admission {
+    Allowed,
+    Limited { retry_after: Duration },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_test() {
+        let limit = RateLimit::per_minute(2);
+        assert_eq!(limit.refill(), Duration::seconds(30));
+
+        let tokens = limit.take(2.0, Duration::ZERO).unwrap();
+        let tokens = limit.take(tokens, Duration::ZERO).unwrap();
+        assert_eq!(limit.take(tokens, Duration::ZERO), Err(Duration::seconds(30)));
+        assert_eq!(limit.take(tokens, Duration::seconds(20)), Err(Duration::seconds(10)));
+        assert_eq!(limit.take(tokens, Duration::seconds(30)), Ok(0.0));
+
+        // It never holds more than `burst`.
+        assert_eq!(limit.take(0.0, Duration::hours(1)), Ok(1.0));
+    }
+}
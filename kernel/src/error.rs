@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter};
+use crate::entities::geology::Distance;
 
 #[derive(Debug, thiserror::Error)]
 pub enum KernelError {
@@ -9,6 +10,11 @@ pub enum KernelError {
         entity: &'static str,
         msg: &'static str,
     },
+    #[error("Out of range. {distance} away from the location, should be within {radius}m.")]
+    OutOfRange {
+        distance: Distance,
+        radius: i32,
+    },
     #[error("Try type conversion. from: `{from}` -> to: `{to}`, src: {source}")]
     TryConversion {
         from: &'static str,
@@ -25,6 +31,8 @@ pub enum KernelError {
         ty: &'static str,
         msg: anyhow::Error,
     },
+    #[error("Forbidden. `{scope}` is required.")]
+    Forbidden { scope: &'static str },
     #[error(transparent)]
     Driver(anyhow::Error),
     #[error(transparent)]
@@ -57,9 +65,11 @@ impl From<KernelError> for KernelErrorKind {
         match value {
             KernelError::Validation { .. } => Self::new("validation", value),
             KernelError::Conflict { entity, .. } => Self::new(format!("conflict_{}", entity), value),
+            KernelError::OutOfRange { .. } => Self::new("out_of_range", value),
             KernelError::TryConversion { .. } => Self::new("try_conversion", value),
             KernelError::UnSupportedTypeConversion { .. } => Self::new("unsupported_type_conversion", value),
             KernelError::InvalidFormat { .. } => Self::new("invalid_format", value),
+            KernelError::Forbidden { .. } => Self::new("forbidden", value),
             KernelError::Driver(_) => Self::new("driver", value),
             KernelError::Internal(_) => Self::new("kernel", value),
         }
@@ -1,10 +1,17 @@
-use crate::entities::ring::{Ring, RingId};
+use crate::entities::page::{Cursor, Limit, Page};
+use crate::entities::ring::{Ring, RingId, RingRecord, UserId};
 use crate::error::KernelError;
 use async_trait::async_trait;
 use orbital::export_service;
 
+#[rustfmt::skip]
 #[async_trait]
 #[export_service]
+#[cfg_attr(feature = "mock", mockall::automock)]
 pub trait RingFinder: 'static + Sync + Send {
     async fn find_by_id(&self, id: &RingId) -> Result<Option<Ring>, KernelError>;
+    /// Rings placed by `user` across instances, newest first, starting after `after`.
+    ///
+    /// Rings hidden by moderation are not included.
+    async fn find_by_user(&self, user: &UserId, after: Option<&Cursor>, limit: &Limit) -> Result<Page<RingRecord>, KernelError>;
 }
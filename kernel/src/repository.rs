@@ -1,5 +1,8 @@
+mod admin_key;
+mod audit;
 mod image;
 mod instance;
 mod location;
+mod ring;
 
-pub use self::{image::*, instance::*, location::*};
+pub use self::{admin_key::*, audit::*, image::*, instance::*, location::*, ring::*};
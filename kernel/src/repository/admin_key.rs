@@ -0,0 +1,18 @@
+use crate::entities::token::{AdminKey, AdminKeyId, KeyDigest};
+use crate::error::KernelError;
+use async_trait::async_trait;
+use orbital::export_service;
+use time::OffsetDateTime;
+
+#[rustfmt::skip]
+#[async_trait]
+#[export_service]
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait AdminKeyRepository: 'static + Sync + Send {
+    async fn create(&self, create: &AdminKey) -> Result<(), KernelError>;
+    /// Revoke the key of `id`. Returns `false` if there is no such key, or it was already revoked.
+    async fn revoke(&self, id: &AdminKeyId, at: &OffsetDateTime) -> Result<bool, KernelError>;
+    async fn touch(&self, id: &AdminKeyId, at: &OffsetDateTime) -> Result<(), KernelError>;
+    async fn find_all(&self) -> Result<Vec<AdminKey>, KernelError>;
+    async fn find_by_digest(&self, digest: &KeyDigest) -> Result<Option<AdminKey>, KernelError>;
+}
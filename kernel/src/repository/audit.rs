@@ -0,0 +1,15 @@
+use crate::entities::audit::{AuditEntry, AuditFilter};
+use crate::entities::page::{Cursor, Limit, Page};
+use crate::error::KernelError;
+use async_trait::async_trait;
+use orbital::export_service;
+
+#[rustfmt::skip]
+#[async_trait]
+#[export_service]
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait AuditLog: 'static + Sync + Send {
+    async fn record(&self, entry: &AuditEntry) -> Result<(), KernelError>;
+    /// Entries matching `filter`, newest first, starting after `after`.
+    async fn find_page(&self, filter: &AuditFilter, after: Option<&Cursor>, limit: &Limit) -> Result<Page<AuditEntry>, KernelError>;
+}
@@ -6,6 +6,7 @@ use orbital::export_service;
 
 #[async_trait]
 #[export_service]
+#[cfg_attr(feature = "mock", mockall::automock)]
 pub trait ImageRepository: 'static + Send + Sync {
     async fn create(&self, image: &Image) -> Result<(), KernelError>;
     async fn delete(&self, id: &RingId) -> Result<(), KernelError>;
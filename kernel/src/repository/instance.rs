@@ -1,8 +1,9 @@
-use crate::entities::instance::{Instance, InstanceId};
+use crate::entities::instance::{Instance, InstanceFilter, InstanceId};
+use crate::entities::location::LocationId;
+use crate::entities::page::{Cursor, Limit, Page};
 use crate::error::KernelError;
 use async_trait::async_trait;
 use orbital::export_service;
-use std::collections::BTreeSet;
 
 #[rustfmt::skip]
 #[async_trait]
@@ -12,7 +13,11 @@ pub trait InstanceRepository: 'static + Sync + Send {
     async fn create(&self, create: &Instance) -> Result<(), KernelError>;
     async fn update(&self, update: &Instance) -> Result<(), KernelError>;
     async fn delete(&self, delete: &InstanceId) -> Result<(), KernelError>;
-    async fn find_all(&self) -> Result<BTreeSet<Instance>, KernelError>;
+    /// Instances matching `filter`, newest first, starting after `after`.
+    ///
+    /// Instances in the page are without their rings.
+    async fn find_page(&self, filter: &InstanceFilter, after: Option<&Cursor>, limit: &Limit) -> Result<Page<Instance>, KernelError>;
     async fn find_by_id(&self, id: &InstanceId) -> Result<Option<Instance>, KernelError>;
-    async fn find_unfinished(&self) -> Result<Option<Instance>, KernelError>;
+    async fn find_unfinished(&self, location: &LocationId) -> Result<Option<Instance>, KernelError>;
+    async fn find_all_unfinished(&self) -> Result<Vec<Instance>, KernelError>;
 }
@@ -1,17 +1,23 @@
+use crate::entities::audit::AuditEntry;
+use crate::entities::geology::{Distance, Position};
 use crate::entities::location::{LocalizeId, Location, LocationId};
+use crate::entities::page::Limit;
 use crate::error::KernelError;
 use async_trait::async_trait;
 use orbital::export_service;
 
+/// Every change is written along with its `audit` entry, in one transaction.
 #[rustfmt::skip]
 #[async_trait]
 #[export_service]
 #[cfg_attr(feature = "mock", mockall::automock)]
 pub trait LocationRepository: 'static + Send + Sync {
-    async fn create(&self, create: &Location) -> Result<(), KernelError>;
-    async fn update(&self, update: &Location) -> Result<(), KernelError>;
-    async fn delete(&self, delete: &LocationId) -> Result<(), KernelError>;
-    async fn delete_localize(&self, delete: &LocationId, code: &LocalizeId) -> Result<(), KernelError>;
+    async fn create(&self, create: &Location, audit: &AuditEntry) -> Result<(), KernelError>;
+    async fn update(&self, update: &Location, audit: &AuditEntry) -> Result<(), KernelError>;
+    async fn delete(&self, delete: &LocationId, audit: &AuditEntry) -> Result<(), KernelError>;
+    async fn delete_localize(&self, delete: &LocationId, code: &LocalizeId, audit: &AuditEntry) -> Result<(), KernelError>;
     async fn find_all(&self) -> Result<Vec<Location>, KernelError>;
     async fn find_by_id(&self, id: &LocationId) -> Result<Option<Location>, KernelError>;
+    /// Locations within `within` of `center`, nearest first, along with the distance to each.
+    async fn find_nearby(&self, center: &Position, within: &Distance, limit: &Limit) -> Result<Vec<(Location, Distance)>, KernelError>;
 }
\ No newline at end of file
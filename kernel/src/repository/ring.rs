@@ -0,0 +1,18 @@
+use crate::entities::audit::AuditEntry;
+use crate::entities::ring::{Ring, RingId};
+use crate::error::KernelError;
+use async_trait::async_trait;
+use orbital::export_service;
+
+/// Changes to a ring after it was placed.
+///
+/// Placing a ring is done through [`InstanceRepository::update`](crate::repository::InstanceRepository::update).
+/// Every change is written along with its `audit` entry, in one transaction.
+#[rustfmt::skip]
+#[async_trait]
+#[export_service]
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait RingRepository: 'static + Sync + Send {
+    async fn update(&self, update: &Ring, audit: &AuditEntry) -> Result<(), KernelError>;
+    async fn delete(&self, delete: &RingId, audit: &AuditEntry) -> Result<(), KernelError>;
+}
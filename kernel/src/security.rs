@@ -1,3 +1,5 @@
 mod authorize;
+mod device;
 
 pub use self::authorize::*;
+pub use self::device::*;
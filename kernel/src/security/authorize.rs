@@ -1,4 +1,4 @@
-use crate::entities::token::AdminToken;
+use crate::entities::token::{AdminKeyId, AdminToken, Scope};
 use crate::error::KernelError;
 use async_trait::async_trait;
 use orbital::export_service;
@@ -6,5 +6,7 @@ use orbital::export_service;
 #[async_trait]
 #[export_service]
 pub trait AuthorizeAdminPolicy: 'static + Sync + Send {
-    async fn authorize(&self, token: &AdminToken) -> Result<(), KernelError>;
+    /// The key the token belongs to.
+    /// Fails with [`KernelError::Forbidden`] if the token is valid but lacks `scope`.
+    async fn authorize(&self, token: &AdminToken, scope: &Scope) -> Result<AdminKeyId, KernelError>;
 }
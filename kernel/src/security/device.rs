@@ -0,0 +1,12 @@
+use crate::entities::ring::UserId;
+use crate::entities::token::DeviceToken;
+use crate::error::KernelError;
+use orbital::export_service;
+
+#[export_service]
+pub trait DeviceIdentity: 'static + Sync + Send {
+    /// Identity for a newly registered device.
+    fn issue(&self) -> (UserId, DeviceToken);
+    /// The user `token` was issued to, if it was signed by this server.
+    fn verify(&self, token: &DeviceToken) -> Result<UserId, KernelError>;
+}
@@ -5,6 +5,9 @@ use orbital::export_service;
 
 #[async_trait]
 #[export_service]
+#[cfg_attr(feature = "mock", mockall::automock)]
 pub trait ImageExportExternalStorageService: 'static + Send + Sync {
     async fn export(&self, image: &Image, metadata: MetaData) -> Result<(), KernelError>;
+    /// Remove the objects stored by [`ImageExportExternalStorageService::export`].
+    async fn delete(&self, image: &Image) -> Result<(), KernelError>;
 }
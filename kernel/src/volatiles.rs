@@ -1,3 +1,9 @@
+mod event;
+mod presence;
 mod locations;
+mod rate;
 
-pub use locations::*;
\ No newline at end of file
+pub use event::*;
+pub use presence::*;
+pub use locations::*;
+pub use rate::*;
@@ -0,0 +1,33 @@
+use crate::entities::location::LocationId;
+use crate::entities::volatiles::Event;
+use crate::error::KernelError;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use orbital::export_service;
+use serde_json::Value;
+
+pub type EventStream = BoxStream<'static, Event>;
+
+/// Fan-out of [`Event`]s between server nodes.
+#[rustfmt::skip]
+#[async_trait]
+#[export_service]
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait EventBus: 'static + Sync + Send {
+    /// Number `payload` within `location` and deliver it to every subscriber, on every node.
+    async fn publish(&self, location: &LocationId, payload: Value) -> Result<u64, KernelError>;
+    /// Events published from now on, in the order of their `seq` within each location.
+    async fn subscribe(&self) -> Result<EventStream, KernelError>;
+}
+
+/// So that the implementation can be chosen at startup.
+#[async_trait]
+impl EventBus for Box<dyn EventBus> {
+    async fn publish(&self, location: &LocationId, payload: Value) -> Result<u64, KernelError> {
+        self.as_ref().publish(location, payload).await
+    }
+
+    async fn subscribe(&self) -> Result<EventStream, KernelError> {
+        self.as_ref().subscribe().await
+    }
+}
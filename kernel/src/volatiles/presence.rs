@@ -0,0 +1,28 @@
+use crate::entities::location::LocationId;
+use crate::entities::volatiles::{Presence, SessionId, Viewers};
+use crate::error::KernelError;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use orbital::export_service;
+use time::Duration;
+
+pub type PresenceStream = BoxStream<'static, Presence>;
+
+#[rustfmt::skip]
+#[async_trait]
+#[export_service]
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait PresenceTracker: 'static + Sync + Send {
+    const NAMESPACE: &'static str = "presence";
+    /// Sessions that are not entered again within this are no longer counted,
+    /// so that the sessions of a crashed server node fade out.
+    const TTL: Duration = Duration::seconds(90);
+    /// Count `session` as watching `location`, or keep it counted for another [`TTL`](Self::TTL).
+    ///
+    /// Like [`leave`](Self::leave) and [`count`](Self::count), it publishes the count if it changed.
+    async fn enter(&self, location: &LocationId, session: &SessionId) -> Result<Viewers, KernelError>;
+    async fn leave(&self, location: &LocationId, session: &SessionId) -> Result<Viewers, KernelError>;
+    async fn count(&self, location: &LocationId) -> Result<Viewers, KernelError>;
+    /// Counts that change from now on, on every node.
+    async fn subscribe(&self) -> Result<PresenceStream, KernelError>;
+}
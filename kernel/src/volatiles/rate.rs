@@ -0,0 +1,14 @@
+use crate::entities::volatiles::{Admission, RateKey, RateLimit};
+use crate::error::KernelError;
+use async_trait::async_trait;
+use orbital::export_service;
+
+#[rustfmt::skip]
+#[async_trait]
+#[export_service]
+#[cfg_attr(feature = "mock", mockall::automock)]
+pub trait RateLimiter: 'static + Sync + Send {
+    const NAMESPACE: &'static str = "rate_limit";
+    /// Take a token from the bucket of `key`, shaped by `limit`.
+    async fn acquire(&self, key: &RateKey, limit: &RateLimit) -> Result<Admission, KernelError>;
+}
@@ -0,0 +1,23 @@
+-- Scope instances to a location again, so that every location has its own unfinished instance.
+ALTER TABLE instances ADD COLUMN location UUID;
+
+-- Instances created while they were shared take over the location of their first ring.
+UPDATE instances
+   SET location = (
+     SELECT rings.location FROM rings WHERE rings.instance = instances.id ORDER BY rings.created_at LIMIT 1
+   );
+
+DELETE FROM instances WHERE location IS NULL;
+
+-- Keep only the latest unfinished instance per location.
+UPDATE instances
+   SET finished_at = clock_timestamp()
+ WHERE finished_at IS NULL
+   AND id NOT IN (
+     SELECT DISTINCT ON (location) id FROM instances WHERE finished_at IS NULL ORDER BY location, started_at DESC
+   );
+
+ALTER TABLE instances ALTER COLUMN location SET NOT NULL;
+ALTER TABLE instances ADD FOREIGN KEY (location) REFERENCES location_mark(id) ON DELETE CASCADE;
+
+CREATE UNIQUE INDEX instances_unfinished_location ON instances(location) WHERE finished_at IS NULL;
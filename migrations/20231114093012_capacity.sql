@@ -0,0 +1,6 @@
+-- Number of rings an instance at the location accepts before it is finished.
+ALTER TABLE location_mark ADD COLUMN capacity INTEGER NOT NULL DEFAULT 70 CHECK (capacity > 0);
+
+-- Capacity is copied from the location when an instance starts,
+-- so that editing a location does not affect the running instance.
+ALTER TABLE instances ADD COLUMN capacity INTEGER NOT NULL DEFAULT 70 CHECK (capacity > 0);
@@ -0,0 +1,3 @@
+-- Revision of an instance for optimistic locking.
+-- Every update must name the revision it was based on, and increments it.
+ALTER TABLE instances ADD COLUMN version BIGINT NOT NULL DEFAULT 0;
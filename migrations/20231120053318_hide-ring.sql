@@ -0,0 +1,2 @@
+-- Rings taken down by moderation. They keep their index, and can be restored.
+ALTER TABLE rings ADD COLUMN hidden_at TIMESTAMPTZ;
@@ -0,0 +1,2 @@
+-- Keyset pagination of `GET /rings` walks instances newest first.
+CREATE INDEX instances_started_at ON instances (started_at DESC, id DESC);
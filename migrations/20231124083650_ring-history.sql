@@ -0,0 +1,2 @@
+-- History of a user walks their rings newest first.
+CREATE INDEX rings_user_id ON rings (user_id, created_at DESC, id DESC);
@@ -0,0 +1,2 @@
+-- Nearby search filters with `ST_DWithin` on the mark position.
+CREATE INDEX location_mark_location ON location_mark USING GIST (location);
@@ -0,0 +1,11 @@
+-- Admin API keys, replacing the token generated at boot. Only the SHA-256 of a token is stored.
+CREATE TABLE admin_keys(
+  id           UUID        NOT NULL PRIMARY KEY,
+  name         TEXT        NOT NULL,
+  digest       TEXT        NOT NULL UNIQUE,
+  scopes       TEXT[]      NOT NULL,
+  created_at   TIMESTAMPTZ NOT NULL,
+  expires_at   TIMESTAMPTZ,
+  last_used_at TIMESTAMPTZ,
+  revoked_at   TIMESTAMPTZ
+);
@@ -0,0 +1,14 @@
+-- Every admin mutation, with the state of its target before and after it.
+CREATE TABLE audit_log(
+  id          UUID        NOT NULL PRIMARY KEY,
+  actor       UUID        NOT NULL REFERENCES admin_keys(id),
+  action      TEXT        NOT NULL,
+  target      UUID        NOT NULL,
+  before      JSONB,
+  after       JSONB,
+  recorded_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX audit_log_recorded_at_idx ON audit_log(recorded_at DESC, id DESC);
+CREATE INDEX audit_log_target_idx ON audit_log(target, recorded_at DESC);
+CREATE INDEX audit_log_actor_idx ON audit_log(actor, recorded_at DESC);
@@ -0,0 +1,151 @@
+use crate::error::ServerError;
+use driver::database::AdminKeyDataBase;
+use driver::DataBaseInitializer;
+use kernel::entities::token::{AdminKey, AdminKeyId, KeyName, Scope};
+use kernel::external::time::{Duration, OffsetDateTime};
+use kernel::external::uuid::Uuid;
+use kernel::repository::AdminKeyRepository;
+use std::collections::BTreeSet;
+
+const USAGE: &str = "\
+usage: server admin-key issue <name> [--scope <scope>]... [--expires-in <days>]
+       server admin-key revoke <id>
+       server admin-key list
+
+scopes: * (default), locations:write, rings:moderate, audit:read";
+
+/// Management of admin keys from the command line, so that no admin session is needed to get the first one.
+#[derive(Debug, PartialEq)]
+pub enum KeyCommand {
+    Issue {
+        name: String,
+        scopes: Vec<String>,
+        expires_in: Option<Duration>,
+    },
+    Revoke {
+        id: Uuid,
+    },
+    List,
+}
+
+impl KeyCommand {
+    /// Parse the arguments after the program name. `None` means serving as usual.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<KeyCommand>, ServerError> {
+        let mut args = args.into_iter();
+        if args.next().as_deref() != Some("admin-key") {
+            return Ok(None);
+        }
+        let invalid = || ServerError::InvalidCommand(USAGE.to_string());
+
+        let command = match args.next().as_deref() {
+            Some("issue") => {
+                let name = args.next().ok_or_else(invalid)?;
+                let mut scopes = Vec::new();
+                let mut expires_in = None;
+                while let Some(flag) = args.next() {
+                    let value = args.next().ok_or_else(invalid)?;
+                    match flag.as_str() {
+                        "--scope" => scopes.push(value),
+                        "--expires-in" => {
+                            let days = value.parse::<i64>().ok().filter(|days| *days > 0).ok_or_else(invalid)?;
+                            expires_in = Some(Duration::days(days));
+                        }
+                        _ => return Err(invalid()),
+                    }
+                }
+                if scopes.is_empty() {
+                    scopes.push(Scope::All.to_string());
+                }
+                KeyCommand::Issue { name, scopes, expires_in }
+            }
+            Some("revoke") => {
+                let id = args.next().and_then(|id| id.parse().ok()).ok_or_else(invalid)?;
+                KeyCommand::Revoke { id }
+            }
+            Some("list") => KeyCommand::List,
+            _ => return Err(invalid()),
+        };
+        if args.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(Some(command))
+    }
+
+    pub async fn run(self) -> Result<(), ServerError> {
+        let pg_url = dotenvy::var("PG_DATABASE_URL")
+            .map_err(|_| ServerError::EnvError(r#"PG_DATABASE_URL"#))?;
+        let keys = AdminKeyDataBase::new(DataBaseInitializer::setup_postgres(pg_url).await?);
+
+        match self {
+            KeyCommand::Issue { name, scopes, expires_in } => {
+                let scopes = scopes
+                    .iter()
+                    .map(|scope| scope.parse::<Scope>())
+                    .collect::<Result<BTreeSet<_>, _>>()?;
+                let expires_at = expires_in.map(|expires_in| OffsetDateTime::now_utc() + expires_in);
+                let (key, token) = AdminKey::issue(KeyName::new(name)?, scopes, expires_at)?;
+                keys.create(&key).await?;
+                println!("Issued admin key {}.", key.id());
+                println!("{}", token.as_ref());
+                println!("Keep the token above, it is not shown again.");
+            }
+            KeyCommand::Revoke { id } => {
+                let now = OffsetDateTime::now_utc();
+                if !keys.revoke(&AdminKeyId::new(id), &now).await? {
+                    return Err(ServerError::NotFound {
+                        entity: "AdminKey",
+                        target: id.to_string(),
+                    });
+                }
+                println!("Revoked admin key {id}.");
+            }
+            KeyCommand::List => {
+                let now = OffsetDateTime::now_utc();
+                for key in keys.find_all().await? {
+                    let scopes = key.scopes().iter().map(Scope::to_string).collect::<Vec<_>>().join(",");
+                    let state = match (key.revoked_at(), key.is_active(now)) {
+                        (Some(_), _) => "revoked",
+                        (None, false) => "expired",
+                        (None, true) => "active",
+                    };
+                    let last_used = key.last_used_at().map_or("never".to_string(), |at| at.to_string());
+                    println!("{}\t{}\t{scopes}\t{state}\tlast used: {last_used}", key.id(), key.name().as_ref());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Option<KeyCommand>, ServerError> {
+        KeyCommand::parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parse_test() -> anyhow::Result<()> {
+        assert_eq!(parse("")?, None);
+        assert_eq!(parse("--debug-assertion")?, None);
+        assert_eq!(parse("admin-key list")?, Some(KeyCommand::List));
+        assert_eq!(
+            parse("admin-key issue venue --expires-in 30")?,
+            Some(KeyCommand::Issue {
+                name: "venue".to_string(),
+                scopes: vec!["*".to_string()],
+                expires_in: Some(Duration::days(30)),
+            })
+        );
+        let id = Uuid::new_v4();
+        assert_eq!(parse(&format!("admin-key revoke {id}"))?, Some(KeyCommand::Revoke { id }));
+
+        assert!(parse("admin-key").is_err());
+        assert!(parse("admin-key issue").is_err());
+        assert!(parse("admin-key issue venue --expires-in 0").is_err());
+        assert!(parse("admin-key revoke not-a-uuid").is_err());
+        assert!(parse("admin-key list extra").is_err());
+        Ok(())
+    }
+}
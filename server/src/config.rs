@@ -0,0 +1,289 @@
+use crate::error::ServerError;
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE, IF_NONE_MATCH};
+use axum::http::{HeaderName, HeaderValue, Method};
+use kernel::entities::instance::Expiry;
+use kernel::entities::volatiles::RateLimit;
+use kernel::external::time::Duration;
+
+/// Settings of the server read from the environment.
+///
+/// Unlike the connection settings in [`Handler`](crate::Handler), all of these are optional.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// When an unfinished instance is rolled over. (`INSTANCE_MAX_AGE`, `INSTANCE_IDLE_TIMEOUT`)
+    pub expiry: Expiry,
+    /// How often instances are checked for expiry. (`INSTANCE_EXPIRY_INTERVAL`)
+    pub expiry_interval: std::time::Duration,
+    pub socket: SocketConfig,
+    pub rate: RateConfig,
+    /// CORS policy of every route. (`CORS_*`)
+    pub cors: CorsConfig,
+    /// CORS policy of the admin mutations at `/locations`, falling back to [`Config::cors`]. (`ADMIN_CORS_*`)
+    pub admin_cors: CorsConfig,
+    pub headers: HeadersConfig,
+    pub image: ImageConfig,
+}
+
+/// Settings of the websocket at `/ws-rings`.
+#[derive(Debug, Clone)]
+pub struct SocketConfig {
+    /// Messages a room holds for each client before it lags behind. (`WS_CHANNEL_CAPACITY`)
+    pub capacity: usize,
+    /// How often clients are pinged. A client that has not answered by the next ping is disconnected. (`WS_PING_INTERVAL`)
+    pub ping_interval: std::time::Duration,
+    /// Disconnect clients that have sent nothing for this long, off unless set. (`WS_IDLE_TIMEOUT`)
+    ///
+    /// Pongs do not count, so it also disconnects viewers that only watch the room.
+    pub idle_timeout: Option<std::time::Duration>,
+    /// Connections across all clients. (`WS_MAX_CONNECTIONS`)
+    pub max_connections: usize,
+    /// Connections from one IP address. (`WS_MAX_CONNECTIONS_PER_ADDR`)
+    pub max_connections_per_addr: usize,
+}
+
+impl Default for SocketConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            ping_interval: std::time::Duration::from_secs(30),
+            idle_timeout: None,
+            max_connections: 10_000,
+            max_connections_per_addr: 32,
+        }
+    }
+}
+
+/// Rate limits of ring creation, over both HTTP and the websocket, and of device registration. `None` is not limited.
+#[derive(Debug, Clone)]
+pub struct RateConfig {
+    /// Rings a minute for each user. (`RING_RATE_PER_USER`)
+    pub per_user: Option<RateLimit>,
+    /// Rings a minute from each IP address. (`RING_RATE_PER_ADDR`)
+    pub per_addr: Option<RateLimit>,
+    /// Devices a minute registered from each IP address. (`DEVICE_RATE_PER_ADDR`)
+    pub devices_per_addr: Option<RateLimit>,
+}
+
+impl Default for RateConfig {
+    fn default() -> Self {
+        Self {
+            per_user: Some(RateLimit::per_minute(10)),
+            per_addr: Some(RateLimit::per_minute(120)),
+            devices_per_addr: Some(RateLimit::per_minute(10)),
+        }
+    }
+}
+
+/// Cross-origin requests accepted from browsers.
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    /// `None` allows any origin. (`CORS_ALLOWED_ORIGINS`, `*` or comma separated)
+    pub origins: Option<Vec<HeaderValue>>,
+    /// (`CORS_ALLOWED_METHODS`, comma separated)
+    pub methods: Vec<Method>,
+    /// Request headers other than the CORS-safelisted ones. (`CORS_ALLOWED_HEADERS`, comma separated)
+    pub headers: Vec<HeaderName>,
+    /// Whether cookies and `Authorization` are sent along. Needs explicit origins. (`CORS_ALLOW_CREDENTIALS`)
+    pub credentials: bool,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            origins: None,
+            methods: vec![Method::GET, Method::POST, Method::PATCH, Method::DELETE],
+            headers: vec![AUTHORIZATION, CONTENT_TYPE, IF_NONE_MATCH],
+            credentials: false,
+        }
+    }
+}
+
+/// Security headers added to every response.
+#[derive(Debug, Clone, Default)]
+pub struct HeadersConfig {
+    /// `max-age` of `Strict-Transport-Security`, only sent when set. (`HSTS_MAX_AGE`)
+    pub hsts_max_age: Option<std::time::Duration>,
+}
+
+/// Settings of the image upload at `/images`.
+#[derive(Debug, Clone)]
+pub struct ImageConfig {
+    /// Size of an image after decoding. (`IMAGE_MAX_BYTES`)
+    pub max_bytes: usize,
+}
+
+impl ImageConfig {
+    /// Limit of the whole request body.
+    ///
+    /// It leaves room for the base64 of the JSON form, which is 4/3 of the image, and the other fields.
+    pub fn body_limit(&self) -> usize {
+        self.max_bytes.saturating_add(self.max_bytes / 3 + 4).saturating_add(64 * 1024)
+    }
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_env() -> Result<Config, ServerError> {
+        let max_age = seconds("INSTANCE_MAX_AGE", Duration::days(7))?;
+        let idle = seconds("INSTANCE_IDLE_TIMEOUT", Duration::days(1))?;
+        // The check itself can not be disabled, so `0` falls back to the default.
+        let interval = seconds("INSTANCE_EXPIRY_INTERVAL", Duration::minutes(1))?
+            .and_then(|interval| interval.try_into().ok())
+            .unwrap_or(std::time::Duration::from_secs(60));
+
+        let default = SocketConfig::default();
+        let socket = SocketConfig {
+            capacity: count("WS_CHANNEL_CAPACITY", default.capacity)?,
+            // A ping can not be disabled, so `0` falls back to the default.
+            ping_interval: seconds("WS_PING_INTERVAL", Duration::seconds(30))?
+                .and_then(|interval| interval.try_into().ok())
+                .unwrap_or(default.ping_interval),
+            idle_timeout: seconds("WS_IDLE_TIMEOUT", Duration::ZERO)?
+                .and_then(|timeout| timeout.try_into().ok()),
+            max_connections: count("WS_MAX_CONNECTIONS", default.max_connections)?,
+            max_connections_per_addr: count("WS_MAX_CONNECTIONS_PER_ADDR", default.max_connections_per_addr)?,
+        };
+
+        let rate = RateConfig {
+            per_user: per_minute("RING_RATE_PER_USER", 10)?,
+            per_addr: per_minute("RING_RATE_PER_ADDR", 120)?,
+            devices_per_addr: per_minute("DEVICE_RATE_PER_ADDR", 10)?,
+        };
+
+        let cors = cors_policy(
+            ["CORS_ALLOWED_ORIGINS", "CORS_ALLOWED_METHODS", "CORS_ALLOWED_HEADERS", "CORS_ALLOW_CREDENTIALS"],
+            CorsConfig::default(),
+        )?;
+        let admin_cors = cors_policy(
+            ["ADMIN_CORS_ALLOWED_ORIGINS", "ADMIN_CORS_ALLOWED_METHODS", "ADMIN_CORS_ALLOWED_HEADERS", "ADMIN_CORS_ALLOW_CREDENTIALS"],
+            cors.clone(),
+        )?;
+
+        let headers = HeadersConfig {
+            // Unlike the others, it is disabled by default.
+            hsts_max_age: seconds("HSTS_MAX_AGE", Duration::ZERO)?
+                .filter(|max_age| max_age.is_positive())
+                .and_then(|max_age| max_age.try_into().ok()),
+        };
+
+        let image = ImageConfig {
+            max_bytes: count("IMAGE_MAX_BYTES", ImageConfig::default().max_bytes)?,
+        };
+
+        Ok(Self {
+            expiry: Expiry::new(max_age, idle),
+            expiry_interval: interval,
+            socket,
+            rate,
+            cors,
+            admin_cors,
+            headers,
+            image,
+        })
+    }
+}
+
+/// Read `key` as a number of seconds. `0` means disabled.
+fn seconds(key: &'static str, default: Duration) -> Result<Option<Duration>, ServerError> {
+    let Ok(value) = dotenvy::var(key) else {
+        return Ok(Some(default));
+    };
+    let secs = value
+        .parse::<i64>()
+        .map_err(|_| ServerError::InvalidEnv(key))?;
+    Ok((secs > 0).then(|| Duration::seconds(secs)))
+}
+
+/// Read `key` as a positive count.
+fn count(key: &'static str, default: usize) -> Result<usize, ServerError> {
+    let Ok(value) = dotenvy::var(key) else {
+        return Ok(default);
+    };
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|count| *count > 0)
+        .ok_or(ServerError::InvalidEnv(key))
+}
+
+/// Read `key` as a number of requests a minute. `0` means disabled.
+fn per_minute(key: &'static str, default: u32) -> Result<Option<RateLimit>, ServerError> {
+    let count = match dotenvy::var(key) {
+        Ok(value) => value
+            .parse::<u32>()
+            .map_err(|_| ServerError::InvalidEnv(key))?,
+        Err(_) => default,
+    };
+    Ok((count > 0).then(|| RateLimit::per_minute(count)))
+}
+
+/// Read a CORS policy from `[origins, methods, headers, credentials]` keys, each falling back to `default`.
+fn cors_policy(keys: [&'static str; 4], default: CorsConfig) -> Result<CorsConfig, ServerError> {
+    let [origins_key, methods_key, headers_key, credentials_key] = keys;
+
+    let origins = match list(origins_key) {
+        Some(origins) if origins.iter().any(|origin| origin == "*") => None,
+        Some(origins) => Some(
+            origins
+                .iter()
+                .map(|origin| HeaderValue::from_str(origin))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ServerError::InvalidEnv(origins_key))?,
+        ),
+        None => default.origins,
+    };
+    let methods = match list(methods_key) {
+        Some(methods) => methods
+            .iter()
+            .map(|method| method.to_ascii_uppercase().parse::<Method>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ServerError::InvalidEnv(methods_key))?,
+        None => default.methods,
+    };
+    let headers = match list(headers_key) {
+        Some(headers) => headers
+            .iter()
+            .map(|header| header.parse::<HeaderName>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ServerError::InvalidEnv(headers_key))?,
+        None => default.headers,
+    };
+    let credentials = match dotenvy::var(credentials_key) {
+        Ok(value) => value
+            .parse::<bool>()
+            .map_err(|_| ServerError::InvalidEnv(credentials_key))?,
+        Err(_) => default.credentials,
+    };
+
+    // Browsers reject credentials with a wildcard origin, so it is a misconfiguration.
+    if credentials && origins.is_none() {
+        return Err(ServerError::InvalidEnv(credentials_key));
+    }
+
+    Ok(CorsConfig {
+        origins,
+        methods,
+        headers,
+        credentials,
+    })
+}
+
+/// Read `key` as a comma separated list. Empty items are skipped.
+fn list(key: &'static str) -> Option<Vec<String>> {
+    let value = dotenvy::var(key).ok()?;
+    Some(
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(ToOwned::to_owned)
+            .collect(),
+    )
+}
@@ -1,7 +1,11 @@
+mod audit;
+mod devices;
 mod images;
 mod locations;
 mod rings;
 
+pub use self::audit::*;
+pub use self::devices::*;
 pub use self::images::*;
 pub use self::locations::*;
 pub use self::rings::*;
@@ -40,7 +44,7 @@ use std::{future::IntoFuture, marker::PhantomData};
 ///
 /// impl Intake<ViewModel> for Transformer {
 ///     type To = TextInputDto;
-///     fn emit(&self, input: ViewModel) -> Self::Dto {
+///     fn emit(&self, input: ViewModel) -> Self::To {
 ///         TextInputDto {
 ///             text: input.text
 ///         }
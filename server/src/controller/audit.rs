@@ -0,0 +1,92 @@
+use crate::controller::{Exhaust, TryIntake};
+use crate::error::ServerError;
+use crate::routes::AuditQuery;
+use kernel::entities::audit::{AuditAction, AuditEntry, AuditFilter, DestructAuditEntry};
+use kernel::entities::instance::Period;
+use kernel::entities::page::{Cursor, Limit, Page};
+use kernel::entities::token::AdminKeyId;
+use kernel::external::time::OffsetDateTime;
+use kernel::external::uuid::Uuid;
+use serde::Serialize;
+use serde_json::Value;
+
+pub struct AuditQueryToPage;
+
+impl TryIntake<AuditQuery> for AuditQueryToPage {
+    type To = (AuditFilter, Option<Cursor>, Limit);
+    type Error = ServerError;
+
+    fn emit(&self, input: AuditQuery) -> Result<Self::To, Self::Error> {
+        let invalid = |e| ServerError::IO(anyhow::Error::new(e));
+
+        let cursor = input.cursor.map(Cursor::decode).transpose().map_err(invalid)?;
+        let limit = input.limit.map(Limit::new).transpose().map_err(invalid)?.unwrap_or_default();
+        let action = input.action.as_deref().map(str::parse::<AuditAction>).transpose().map_err(invalid)?;
+        let recorded = Period::new(input.since, input.until).map_err(invalid)?;
+        let filter = AuditFilter::new(
+            input.actor.map(AdminKeyId::new),
+            action,
+            input.target,
+            recorded,
+        );
+
+        Ok((filter, cursor, limit))
+    }
+}
+
+pub struct AuditPageToJson;
+
+impl Exhaust<Page<AuditEntry>> for AuditPageToJson {
+    type To = AuditPage;
+
+    fn emit(&self, input: Page<AuditEntry>) -> Self::To {
+        let (entries, next) = input.into_parts();
+        AuditPage {
+            entries: entries.into_iter().map(Into::into).collect(),
+            next: next.map(|cursor| cursor.encode()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditPage {
+    entries: Vec<AuditRecord>,
+    /// Pass as `cursor` to get the following page. Absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditRecord {
+    id: Uuid,
+    actor: Uuid,
+    action: &'static str,
+    target: Uuid,
+    before: Option<Value>,
+    after: Option<Value>,
+    #[serde(with = "kernel::external::time::serde::iso8601")]
+    recorded_at: OffsetDateTime,
+}
+
+impl From<AuditEntry> for AuditRecord {
+    fn from(value: AuditEntry) -> Self {
+        let DestructAuditEntry {
+            id,
+            actor,
+            action,
+            target,
+            before,
+            after,
+            recorded_at,
+        } = value.into_destruct();
+        Self {
+            id,
+            actor: actor.into(),
+            action: action.as_str(),
+            target,
+            before,
+            after,
+            recorded_at,
+        }
+    }
+}
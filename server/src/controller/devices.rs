@@ -0,0 +1,24 @@
+use crate::controller::Exhaust;
+use kernel::entities::ring::UserId;
+use kernel::entities::token::DeviceToken;
+use kernel::external::uuid::Uuid;
+use serde::Serialize;
+
+pub struct IssuedDeviceToResponse;
+
+impl Exhaust<(UserId, DeviceToken)> for IssuedDeviceToResponse {
+    type To = DeviceResponse;
+    fn emit(&self, (user, token): (UserId, DeviceToken)) -> Self::To {
+        DeviceResponse {
+            user: user.into(),
+            token: token.into(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeviceResponse {
+    user: Uuid,
+    /// Sent back as `Authorization: Bearer <token>`, or `?token=` on the websocket.
+    token: String,
+}
@@ -1,9 +1,13 @@
-use crate::controller::Intake;
+use crate::controller::{Intake, TryIntake};
+use crate::error::ServerError;
 use application::transfer::CreateImageDto;
+use kernel::entities::image::ImageBin;
+use kernel::entities::ring::UserId;
 use kernel::external::time::OffsetDateTime;
 use kernel::external::uuid::Uuid;
 use serde::Deserialize;
 
+/// The JSON form, with the image in base64. Kept for clients older than [`ImageUpload`].
 #[derive(Deserialize)]
 pub struct CreateImageRequest {
     pub ring_id: Uuid,
@@ -12,13 +16,49 @@ pub struct CreateImageRequest {
     pub created_at: OffsetDateTime,
 }
 
-pub struct CreateImageRequestToCreateImageDto;
+/// The `multipart/form-data` form, with the image as a binary part.
+#[derive(Debug)]
+pub struct ImageUpload {
+    pub ring_id: Uuid,
+    pub image: Vec<u8>,
+    pub created_at: OffsetDateTime,
+}
+
+pub struct CreateImageRequestToCreateImageDto {
+    /// Size of the image after decoding.
+    pub max_bytes: usize,
+}
+
+/// Takes the user verified from the device token along with the request.
+impl TryIntake<(UserId, CreateImageRequest)> for CreateImageRequestToCreateImageDto {
+    type To = CreateImageDto;
+    type Error = ServerError;
+
+    fn emit(&self, (user, input): (UserId, CreateImageRequest)) -> Result<Self::To, Self::Error> {
+        let bin = ImageBin::from_base64(input.image).map_err(|e| ServerError::IO(anyhow::Error::new(e)))?;
+        if bin.as_ref().len() > self.max_bytes {
+            return Err(ServerError::PayloadTooLarge {
+                limit: self.max_bytes,
+            });
+        }
+        Ok(CreateImageDto {
+            id: input.ring_id,
+            user: user.into(),
+            bin: bin.into(),
+            created_at: input.created_at,
+        })
+    }
+}
+
+pub struct ImageUploadToCreateImageDto;
 
-impl Intake<CreateImageRequest> for CreateImageRequestToCreateImageDto {
+/// Takes the user verified from the device token along with the upload.
+impl Intake<(UserId, ImageUpload)> for ImageUploadToCreateImageDto {
     type To = CreateImageDto;
-    fn emit(&self, input: CreateImageRequest) -> Self::To {
+    fn emit(&self, (user, input): (UserId, ImageUpload)) -> Self::To {
         CreateImageDto {
             id: input.ring_id,
+            user: user.into(),
             bin: input.image,
             created_at: input.created_at,
         }
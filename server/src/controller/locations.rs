@@ -1,9 +1,11 @@
 mod create;
 mod delete;
+mod nearby;
 mod update;
 
-pub use self::{create::*, delete::DeleteRequestToDeleteLocationDto, update::*};
+pub use self::{create::*, delete::DeleteRequestToDeleteLocationDto, nearby::NearbyQueryToSearch, update::*};
 
 pub mod form {
     pub use super::delete::DeleteRequest;
+    pub use super::nearby::NearbyQuery;
 }
@@ -2,17 +2,18 @@ use crate::controller::TryIntake;
 use crate::error::ServerError;
 use application::transfer::CreateLocationDto;
 use geojson::{Feature, GeoJson};
+use kernel::entities::token::AdminKeyId;
 use serde::Deserialize;
 use std::collections::HashMap;
 
 pub struct GeoJsonToCreateLocationDto;
 
-impl TryIntake<GeoJson> for GeoJsonToCreateLocationDto {
+impl TryIntake<(AdminKeyId, GeoJson)> for GeoJsonToCreateLocationDto {
     type To = CreateLocationDto;
     type Error = ServerError;
 
     // noinspection DuplicatedCode
-    fn emit(&self, input: GeoJson) -> Result<Self::To, Self::Error> {
+    fn emit(&self, (actor, input): (AdminKeyId, GeoJson)) -> Result<Self::To, Self::Error> {
         let Feature {
             geometry,
             properties,
@@ -41,6 +42,8 @@ impl TryIntake<GeoJson> for GeoJsonToCreateLocationDto {
         #[derive(Debug, Deserialize)]
         struct Extensions {
             radius: i32,
+            #[serde(default)]
+            capacity: Option<i32>,
             localize: HashMap<String, String>,
         }
 
@@ -50,13 +53,14 @@ impl TryIntake<GeoJson> for GeoJsonToCreateLocationDto {
         })?;
 
         Ok(CreateLocationDto {
+            actor: actor.into(),
             latitude: point[1],
             longitude: point[0],
             radius: props.radius,
+            capacity: props.capacity,
             localize: props
                 .localize
                 .into_iter()
-                .map(|(k, v)| (k, v))
                 .collect::<Vec<_>>(),
         })
     }
@@ -1,5 +1,6 @@
 use crate::controller::Intake;
 use application::transfer::DeleteLocationDto;
+use kernel::entities::token::AdminKeyId;
 use kernel::external::uuid::Uuid;
 use serde::Deserialize;
 
@@ -11,11 +12,12 @@ pub struct DeleteRequest {
 
 pub struct DeleteRequestToDeleteLocationDto;
 
-impl Intake<DeleteRequest> for DeleteRequestToDeleteLocationDto {
+impl Intake<(AdminKeyId, DeleteRequest)> for DeleteRequestToDeleteLocationDto {
     type To = DeleteLocationDto;
 
-    fn emit(&self, input: DeleteRequest) -> Self::To {
+    fn emit(&self, (actor, input): (AdminKeyId, DeleteRequest)) -> Self::To {
         DeleteLocationDto {
+            actor: actor.into(),
             id: input.id,
             localize: input.localize,
         }
@@ -0,0 +1,82 @@
+use crate::controller::TryIntake;
+use crate::error::ServerError;
+use kernel::entities::geology::{Distance, Position};
+use kernel::entities::page::Limit;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct NearbyQuery {
+    /// `longitude,latitude` of the center.
+    pub near: Option<String>,
+    /// Search radius in meters.
+    pub within: Option<f64>,
+    pub limit: Option<i64>,
+}
+
+pub struct NearbyQueryToSearch;
+
+impl NearbyQueryToSearch {
+    pub const DEFAULT_WITHIN: f64 = 5_000.0;
+    pub const MAX_WITHIN: f64 = 50_000.0;
+}
+
+impl TryIntake<(String, NearbyQuery)> for NearbyQueryToSearch {
+    type To = (Position, Distance, Limit);
+    type Error = ServerError;
+
+    fn emit(&self, input: (String, NearbyQuery)) -> Result<Self::To, Self::Error> {
+        let (near, query) = input;
+        let invalid = |msg: &'static str| ServerError::IO(anyhow::Error::msg(msg));
+
+        let Some((lon, lat)) = near.split_once(',') else {
+            return Err(invalid("`near` should be `longitude,latitude`."));
+        };
+        let lon = lon.trim().parse::<f64>().map_err(|_| invalid("`near` longitude is not a number."))?;
+        let lat = lat.trim().parse::<f64>().map_err(|_| invalid("`near` latitude is not a number."))?;
+        let center = Position::new(lon, lat).map_err(|e| ServerError::IO(anyhow::Error::new(e)))?;
+
+        let within = query.within.unwrap_or(Self::DEFAULT_WITHIN);
+        if !(within > 0.0 && within <= Self::MAX_WITHIN) {
+            return Err(invalid("`within` should be greater than 0 and at most 50000 meters."));
+        }
+
+        let limit = query
+            .limit
+            .map(Limit::new)
+            .transpose()
+            .map_err(|e| ServerError::IO(anyhow::Error::new(e)))?
+            .unwrap_or_default();
+
+        Ok((center, Distance::new(within), limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(within: Option<f64>) -> NearbyQuery {
+        NearbyQuery {
+            near: None,
+            within,
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn parse_test() -> anyhow::Result<()> {
+        let (center, within, limit) =
+            NearbyQueryToSearch.emit(("132.7666, 33.8414".to_string(), query(None)))?;
+        assert_eq!(*center.x().as_ref(), 132.7666);
+        assert_eq!(*center.y().as_ref(), 33.8414);
+        assert_eq!(f64::from(within), NearbyQueryToSearch::DEFAULT_WITHIN);
+        assert_eq!(limit, Limit::default());
+
+        assert!(NearbyQueryToSearch.emit(("132.7666".to_string(), query(None))).is_err());
+        assert!(NearbyQueryToSearch.emit(("east,north".to_string(), query(None))).is_err());
+        assert!(NearbyQueryToSearch.emit(("200,33".to_string(), query(None))).is_err());
+        assert!(NearbyQueryToSearch.emit(("132,33".to_string(), query(Some(0.0)))).is_err());
+        assert!(NearbyQueryToSearch.emit(("132,33".to_string(), query(Some(1e9)))).is_err());
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@ use crate::error::ServerError;
 use application::transfer::UpdateLocationDto;
 use geojson::feature::Id;
 use geojson::{Feature, GeoJson};
+use kernel::entities::token::AdminKeyId;
 use kernel::external::uuid::Uuid;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -10,12 +11,12 @@ use std::str::FromStr;
 
 pub struct GeoJsonToUpdateLocationDto;
 
-impl TryIntake<GeoJson> for GeoJsonToUpdateLocationDto {
+impl TryIntake<(AdminKeyId, GeoJson)> for GeoJsonToUpdateLocationDto {
     type To = UpdateLocationDto;
     type Error = ServerError;
 
     //noinspection DuplicatedCode
-    fn emit(&self, input: GeoJson) -> Result<Self::To, Self::Error> {
+    fn emit(&self, (actor, input): (AdminKeyId, GeoJson)) -> Result<Self::To, Self::Error> {
         let Feature {
             id,
             geometry,
@@ -59,6 +60,8 @@ impl TryIntake<GeoJson> for GeoJsonToUpdateLocationDto {
         #[derive(Debug, Deserialize)]
         struct Extensions {
             radius: i32,
+            #[serde(default)]
+            capacity: Option<i32>,
             localize: HashMap<String, String>,
         }
 
@@ -68,11 +71,13 @@ impl TryIntake<GeoJson> for GeoJsonToUpdateLocationDto {
         })?;
 
         Ok(UpdateLocationDto {
+            actor: actor.into(),
             id,
             latitude: point[1],
             longitude: point[0],
             radius: props.radius,
-            localize: props.localize.into_iter().map(|(c, l)| (c, l)).collect(),
+            capacity: props.capacity,
+            localize: props.localize.into_iter().collect(),
         })
     }
 }
@@ -1,5 +1,13 @@
 mod create;
+mod history;
+mod message;
+mod moderate;
 mod read;
+mod rollover;
 
 pub use self::create::*;
+pub use self::history::*;
+pub use self::message::*;
+pub use self::moderate::*;
 pub use self::read::*;
+pub use self::rollover::*;
@@ -1,21 +1,26 @@
 use crate::controller::{Exhaust, Intake};
 use application::transfer::{CreateRingDto, RingDto};
+use kernel::entities::instance::Allocation;
+use kernel::entities::location::LocationId;
+use kernel::entities::ring::UserId;
 use kernel::external::time::OffsetDateTime;
 use kernel::external::uuid::Uuid;
 use serde::{Deserialize, Serialize};
 
 pub struct RequestToCreateRingDto;
 
-impl Intake<CreateRingRequest> for RequestToCreateRingDto {
+/// Takes the user verified from the device token along with the request.
+impl Intake<(UserId, CreateRingRequest)> for RequestToCreateRingDto {
     type To = CreateRingDto;
-    fn emit(&self, input: CreateRingRequest) -> Self::To {
+    fn emit(&self, (user, input): (UserId, CreateRingRequest)) -> Self::To {
         CreateRingDto {
             location: input.location,
             longitude: input.longitude,
             latitude: input.latitude,
             indexed: input.indexed,
+            allocation: input.allocation,
             hue: input.hue,
-            user: input.user,
+            user: user.into(),
             created_at: input.created_at,
         }
     }
@@ -30,6 +35,7 @@ impl Exhaust<RingDto> for RingDtoToResponseJson {
             id: input.id,
             instance: input.instance,
             location: input.location,
+            indexed: input.indexed,
         }
     }
 }
@@ -47,7 +53,8 @@ impl Exhaust<RingDto> for RingDtoToDetailResponseJson {
             hue: input.hue,
             user: input.user,
             created_at: input.created_at,
-            nonce: None
+            nonce: None,
+            finished: input.finished,
         }
     }
 }
@@ -57,9 +64,12 @@ pub struct CreateRingRequest {
     pub location: Uuid,
     pub longitude: f64,
     pub latitude: f64,
-    pub indexed: i32,
+    /// Request a specific slot. If omitted, the server assigns a free one by `allocation`.
+    #[serde(default)]
+    pub indexed: Option<i32>,
+    #[serde(default)]
+    pub allocation: Allocation,
     pub hue: i32,
-    pub user: Uuid,
     #[serde(with = "kernel::external::time::serde::iso8601")]
     pub created_at: OffsetDateTime,
 }
@@ -77,9 +87,10 @@ pub struct RingResponse {
     id: Uuid,
     instance: Uuid,
     location: Uuid,
+    indexed: i32,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize)]
 pub struct RingDetailResponse {
     id: Uuid,
     instance: Uuid,
@@ -90,5 +101,22 @@ pub struct RingDetailResponse {
     #[serde(with = "kernel::external::time::serde::iso8601")]
     created_at: OffsetDateTime,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub nonce: Option<Uuid>
+    pub nonce: Option<Uuid>,
+    #[serde(skip)]
+    finished: bool,
+}
+
+impl RingDetailResponse {
+    pub fn instance(&self) -> Uuid {
+        self.instance
+    }
+
+    pub fn location(&self) -> LocationId {
+        LocationId::new(self.location)
+    }
+
+    /// Whether this ring filled up the instance.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
 }
\ No newline at end of file
@@ -0,0 +1,107 @@
+use crate::controller::{Exhaust, TryIntake};
+use crate::error::ServerError;
+use kernel::entities::geology::DestructPosition;
+use kernel::entities::page::{Cursor, Limit, Page};
+use kernel::entities::ring::{DestructRing, DestructRingRecord, RingRecord, UserId};
+use kernel::external::time::OffsetDateTime;
+use kernel::external::uuid::Uuid;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// `next` of the previous page.
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+pub struct HistoryQueryToUserPage;
+
+impl TryIntake<(UserId, HistoryQuery)> for HistoryQueryToUserPage {
+    type To = (UserId, Option<Cursor>, Limit);
+    type Error = ServerError;
+
+    fn emit(&self, input: (UserId, HistoryQuery)) -> Result<Self::To, Self::Error> {
+        let (user, query) = input;
+        let invalid = |e| ServerError::IO(anyhow::Error::new(e));
+
+        let cursor = query.cursor.map(Cursor::decode).transpose().map_err(invalid)?;
+        let limit = query.limit.map(Limit::new).transpose().map_err(invalid)?.unwrap_or_default();
+
+        Ok((user, cursor, limit))
+    }
+}
+
+pub struct RingRecordPageToJson;
+
+impl Exhaust<Page<RingRecord>> for RingRecordPageToJson {
+    type To = RingHistoryPage;
+
+    fn emit(&self, input: Page<RingRecord>) -> Self::To {
+        let (records, next) = input.into_parts();
+        RingHistoryPage {
+            rings: records.into_iter().map(Into::into).collect(),
+            next: next.map(|cursor| cursor.encode()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RingHistoryPage {
+    rings: Vec<RingHistoryResponse>,
+    /// Pass as `cursor` to get the following page. Absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RingHistoryResponse {
+    id: Uuid,
+    instance: Uuid,
+    location: Uuid,
+    /// Name of the location by country code.
+    location_name: BTreeMap<String, String>,
+    longitude: f64,
+    latitude: f64,
+    indexed: i32,
+    hue: i32,
+    #[serde(with = "kernel::external::time::serde::iso8601")]
+    created_at: OffsetDateTime,
+    has_image: bool,
+}
+
+impl From<RingRecord> for RingHistoryResponse {
+    fn from(value: RingRecord) -> Self {
+        let DestructRingRecord {
+            ring,
+            instance,
+            localize,
+            has_image,
+        } = value.into_destruct();
+        let DestructRing {
+            id,
+            pos_in,
+            location,
+            indexed,
+            hue,
+            created_at,
+            ..
+        } = ring.into_destruct();
+        let DestructPosition { x, y } = pos_in.into_destruct();
+        Self {
+            id: id.into(),
+            instance: instance.into(),
+            location: location.into(),
+            location_name: localize
+                .into_iter()
+                .map(|loc| (loc.country().as_ref().to_string(), loc.localize().as_ref().to_string()))
+                .collect(),
+            longitude: x.into(),
+            latitude: y.into(),
+            indexed: indexed.into(),
+            hue: hue.into(),
+            created_at: created_at.into(),
+            has_image,
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use crate::controller::{CreateRingRequestWithNonce, ModeratedRingResponse, RingDetailResponse, RingInstanceWithDetail};
+use application::error::ApplicationError;
+use kernel::entities::location::LocationId;
+use kernel::error::KernelError;
+use kernel::external::uuid::Uuid;
+use serde::{Deserialize, Serialize};
+
+/// Version of the websocket protocol, carried by every message as `v`.
+///
+/// Bump it when a message changes in a way that older clients cannot read.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Codes of the close frames sent by the server, other than `1000` on a client request.
+pub mod close_code {
+    /// Too many connections in total or from the same address, reconnect later. (`Try Again Later`)
+    pub const TRY_AGAIN_LATER: u16 = 1013;
+    /// The client sent nothing for the idle timeout.
+    pub const IDLE_TIMEOUT: u16 = 4000;
+    /// The client did not answer a ping before the next one.
+    pub const HEARTBEAT_TIMEOUT: u16 = 4001;
+}
+
+/// Every websocket message in both directions, `{"v": 1, "type": "...", ...}`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Envelope<M> {
+    pub v: u32,
+    /// Position of a broadcast in its room. Clients pass the last one seen as `last_seen_seq` to resume.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    #[serde(flatten)]
+    pub message: M,
+}
+
+impl<M> Envelope<M> {
+    pub fn new(message: M) -> Self {
+        Self {
+            v: PROTOCOL_VERSION,
+            seq: None,
+            message,
+        }
+    }
+
+    pub fn sequenced(seq: u64, message: M) -> Self {
+        Self {
+            seq: Some(seq),
+            ..Self::new(message)
+        }
+    }
+}
+
+/// Message sent by websocket clients.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Watch `location` instead of the current one.
+    ///
+    /// Answered with the broadcasts after `last_seen_seq` if they are still kept, otherwise with `snapshot`.
+    Hello {
+        location: Uuid,
+        #[serde(default)]
+        last_seen_seq: Option<u64>,
+    },
+    /// Answered with `ack` to the sender, and `ring_created` to the room.
+    CreateRing(CreateRingRequestWithNonce),
+    /// Answered with `ack`.
+    Ping {
+        #[serde(default)]
+        nonce: Option<Uuid>,
+    },
+}
+
+/// Message sent by the server.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// First message of a connection.
+    /// If `resumed`, the missed broadcasts follow it instead of a `snapshot`.
+    Hello {
+        context_id: Uuid,
+        location: Uuid,
+        resumed: bool,
+    },
+    /// Current instance of the watched location, carrying the `seq` it is up to date with.
+    Snapshot { instance: RingInstanceWithDetail },
+    RingCreated { ring: RingDetailResponse },
+    InstanceFinished(InstanceFinished),
+    RingHidden { ring: ModeratedRingResponse },
+    RingRestored { ring: ModeratedRingResponse },
+    RingDeleted { ring: ModeratedRingResponse },
+    /// Number of sessions watching `location` changed. It has no `seq`, and is not replayed on resume.
+    Presence { location: Uuid, viewers: u64 },
+    Error {
+        code: ErrorCode,
+        reason: String,
+        context_id: Uuid,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        nonce: Option<Uuid>,
+        /// Seconds to wait before sending again, with `rate_limited`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        retry_after: Option<u64>,
+    },
+    Ack {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        nonce: Option<Uuid>,
+    },
+    Ping,
+}
+
+/// An instance was filled up or expired, `next` is the one that replaces it if already started.
+#[derive(Debug, Serialize)]
+pub struct InstanceFinished {
+    location: Uuid,
+    instance: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next: Option<RingInstanceWithDetail>,
+}
+
+impl InstanceFinished {
+    pub fn new(location: Uuid, instance: Uuid, next: Option<RingInstanceWithDetail>) -> Self {
+        Self { location, instance, next }
+    }
+
+    pub fn location(&self) -> LocationId {
+        LocationId::new(self.location)
+    }
+}
+
+/// Stable error codes of the `error` message. Clients should branch on these, not on `reason`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The message is not valid JSON or not a known `type`.
+    InvalidMessage,
+    /// `v` differs from [`PROTOCOL_VERSION`].
+    UnsupportedVersion,
+    /// The message is well-formed but its content was rejected.
+    InvalidData,
+    /// Conflicted with another request, it may succeed if sent again.
+    Conflict,
+    NotFound,
+    /// The server could not prepare an instance for the location.
+    InstanceGenerate,
+    /// The connection has no device token, which creating rings requires.
+    Unauthorized,
+    /// Too many rings from the user or the address, send again after `retry_after`.
+    RateLimited,
+    Internal,
+}
+
+impl From<&ApplicationError> for ErrorCode {
+    fn from(value: &ApplicationError) -> Self {
+        match value {
+            ApplicationError::Kernel(e) => match e.error {
+                KernelError::Conflict { .. } => ErrorCode::Conflict,
+                _ => ErrorCode::InvalidData,
+            },
+            ApplicationError::NotFound { .. } => ErrorCode::NotFound,
+            ApplicationError::Other(_) => ErrorCode::Internal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn client_message_test() -> anyhow::Result<()> {
+        let location = Uuid::new_v4();
+        let hello = json!({ "v": 1, "type": "hello", "location": location });
+        let hello = serde_json::from_value::<Envelope<ClientMessage>>(hello)?;
+        assert_eq!(hello.v, 1);
+        assert!(matches!(hello.message, ClientMessage::Hello { location: l, last_seen_seq: None } if l == location));
+
+        let resume = json!({ "v": 1, "type": "hello", "location": location, "last_seen_seq": 42 });
+        let resume = serde_json::from_value::<Envelope<ClientMessage>>(resume)?;
+        assert!(matches!(resume.message, ClientMessage::Hello { last_seen_seq: Some(42), .. }));
+
+        let create = json!({
+            "v": 1,
+            "type": "create_ring",
+            "location": location,
+            "longitude": 132.7666,
+            "latitude": 33.8414,
+            "hue": 120,
+            "created_at": "2023-11-26T00:00:00Z",
+            "nonce": Uuid::new_v4(),
+        });
+        let create = serde_json::from_value::<Envelope<ClientMessage>>(create)?;
+        assert!(matches!(create.message, ClientMessage::CreateRing(ref req) if req.nonce.is_some()));
+
+        let unknown = json!({ "v": 1, "type": "shout" });
+        assert!(serde_json::from_value::<Envelope<ClientMessage>>(unknown).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn server_message_test() -> anyhow::Result<()> {
+        let nonce = Uuid::new_v4();
+        let ack = serde_json::to_value(Envelope::new(ServerMessage::Ack { nonce: Some(nonce) }))?;
+        assert_eq!(ack, json!({ "v": PROTOCOL_VERSION, "type": "ack", "nonce": nonce }));
+
+        let ping = serde_json::to_value(Envelope::new(ServerMessage::Ping))?;
+        assert_eq!(ping, json!({ "v": PROTOCOL_VERSION, "type": "ping" }));
+
+        let ping = serde_json::to_value(Envelope::sequenced(7, ServerMessage::Ping))?;
+        assert_eq!(ping, json!({ "v": PROTOCOL_VERSION, "seq": 7, "type": "ping" }));
+
+        let location = Uuid::new_v4();
+        let presence = serde_json::to_value(Envelope::new(ServerMessage::Presence { location, viewers: 3 }))?;
+        assert_eq!(presence, json!({ "v": PROTOCOL_VERSION, "type": "presence", "location": location, "viewers": 3 }));
+
+        let context_id = Uuid::new_v4();
+        let error = serde_json::to_value(Envelope::new(ServerMessage::Error {
+            code: ErrorCode::UnsupportedVersion,
+            reason: "`v` should be 1.".to_string(),
+            context_id,
+            nonce: None,
+            retry_after: None,
+        }))?;
+        assert_eq!(error["type"], "error");
+        assert_eq!(error["code"], "unsupported_version");
+        assert!(error.get("retry_after").is_none());
+
+        let limited = serde_json::to_value(Envelope::new(ServerMessage::Error {
+            code: ErrorCode::RateLimited,
+            reason: "Too many rings, send again later.".to_string(),
+            context_id,
+            nonce: Some(nonce),
+            retry_after: Some(6),
+        }))?;
+        assert_eq!(limited["code"], "rate_limited");
+        assert_eq!(limited["retry_after"], 6);
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::controller::Exhaust;
+use application::transfer::ModeratedRingDto;
+use kernel::entities::location::LocationId;
+use kernel::external::time::OffsetDateTime;
+use kernel::external::uuid::Uuid;
+use serde::Serialize;
+
+pub struct ModeratedRingToResponseJson;
+
+impl Exhaust<ModeratedRingDto> for ModeratedRingToResponseJson {
+    type To = ModeratedRingResponse;
+    fn emit(&self, input: ModeratedRingDto) -> Self::To {
+        ModeratedRingResponse {
+            id: input.id,
+            location: input.location,
+            indexed: input.indexed,
+            hue: input.hue,
+            user: input.user,
+            created_at: input.created_at,
+            hidden: input.hidden,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModeratedRingResponse {
+    id: Uuid,
+    location: Uuid,
+    indexed: i32,
+    hue: i32,
+    user: Uuid,
+    #[serde(with = "kernel::external::time::serde::iso8601")]
+    created_at: OffsetDateTime,
+    hidden: bool,
+}
+
+impl ModeratedRingResponse {
+    pub fn location(&self) -> LocationId {
+        LocationId::new(self.location)
+    }
+}
@@ -1,5 +1,10 @@
-use crate::controller::{Exhaust, Intake};
-use kernel::entities::instance::{Instance, InstanceId};
+use crate::controller::{Exhaust, Intake, TryIntake};
+use crate::error::ServerError;
+use crate::routes::SelectionQuery;
+use kernel::entities::instance::{Instance, InstanceFilter, InstanceId, Period};
+use kernel::entities::location::LocationId;
+use kernel::entities::page::{Cursor, Limit, Page};
+use kernel::entities::ring::UserId;
 use kernel::entities::ring::Ring;
 use kernel::external::time::OffsetDateTime;
 use kernel::external::uuid::Uuid;
@@ -34,25 +39,84 @@ impl Exhaust<Option<Instance>> for MaybeInstanceToDetailResponse {
     }
 }
 
-pub struct InstancesToJsonBTreeSet;
+pub struct SelectionQueryToInstancePage;
 
-impl Exhaust<BTreeSet<Instance>> for InstancesToJsonBTreeSet {
-    type To = BTreeSet<RingInstance>;
+impl TryIntake<SelectionQuery> for SelectionQueryToInstancePage {
+    type To = (InstanceFilter, Option<Cursor>, Limit);
+    type Error = ServerError;
 
-    fn emit(&self, input: BTreeSet<Instance>) -> Self::To {
-        input.into_iter().map(Into::into).collect()
+    fn emit(&self, input: SelectionQuery) -> Result<Self::To, Self::Error> {
+        let invalid = |e| ServerError::IO(anyhow::Error::new(e));
+
+        let cursor = input.cursor.map(Cursor::decode).transpose().map_err(invalid)?;
+        let limit = input.limit.map(Limit::new).transpose().map_err(invalid)?.unwrap_or_default();
+        let started = Period::new(input.started_since, input.started_until).map_err(invalid)?;
+        let finished = Period::new(input.finished_since, input.finished_until).map_err(invalid)?;
+        let filter = InstanceFilter::new(
+            input.location.map(LocationId::new),
+            input.user.map(UserId::new),
+            started,
+            finished,
+            input.finished,
+        );
+
+        Ok((filter, cursor, limit))
+    }
+}
+
+pub struct InstancePageToJson;
+
+impl Exhaust<Page<Instance>> for InstancePageToJson {
+    type To = RingInstancePage;
+
+    fn emit(&self, input: Page<Instance>) -> Self::To {
+        let (instances, next) = input.into_parts();
+        RingInstancePage {
+            instances: instances.into_iter().map(Into::into).collect(),
+            next: next.map(|cursor| cursor.encode()),
+        }
     }
 }
 
+#[derive(Debug, Serialize)]
+pub struct RingInstancePage {
+    instances: Vec<RingInstance>,
+    /// Pass as `cursor` to get the following page. Absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct RingInstanceWithDetail {
     id: Uuid,
+    location: Uuid,
     #[serde(with = "kernel::external::time::serde::iso8601")]
     started_at: OffsetDateTime,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "kernel::external::time::serde::iso8601::option")]
     finished_at: Option<OffsetDateTime>,
+    capacity: i32,
     rings: BTreeSet<Ring>,
+    /// Websocket sessions watching the location, only on the unfinished instance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    viewers: Option<u64>,
+}
+
+impl RingInstanceWithDetail {
+    pub fn location(&self) -> LocationId {
+        LocationId::new(self.location)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    pub fn with_viewers(self, viewers: impl Into<u64>) -> Self {
+        Self {
+            viewers: Some(viewers.into()),
+            ..self
+        }
+    }
 }
 
 impl From<Instance> for RingInstanceWithDetail {
@@ -60,9 +124,14 @@ impl From<Instance> for RingInstanceWithDetail {
         let value = value.into_destruct();
         RingInstanceWithDetail {
             id: value.id.into(),
+            location: value.location.into(),
             started_at: value.started_at.into(),
             finished_at: value.finished_at.into(),
-            rings: value.rings.into(),
+            capacity: (*value.rings.capacity()).into(),
+            rings: value.rings.into_iter()
+                .filter(|ring| !ring.hidden_at().is_hidden())
+                .collect(),
+            viewers: None,
         }
     }
 }
@@ -70,6 +139,7 @@ impl From<Instance> for RingInstanceWithDetail {
 #[derive(Debug, Serialize)]
 pub struct RingInstance {
     id: Uuid,
+    location: Uuid,
     #[serde(with = "kernel::external::time::serde::iso8601")]
     started_at: OffsetDateTime,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -82,6 +152,7 @@ impl From<Instance> for RingInstance {
         let value = value.into_destruct();
         Self {
             id: value.id.into(),
+            location: value.location.into(),
             started_at: value.started_at.into(),
             finished_at: value.finished_at.into(),
         }
@@ -104,6 +175,46 @@ impl Ord for RingInstance {
 
 impl PartialOrd<Self> for RingInstance {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.started_at.partial_cmp(&other.started_at)
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kernel::entities::geology::Position;
+    use kernel::entities::instance::{Capacity, FinishedAt, RingSet, StartedAt, Version};
+    use kernel::entities::ring::{CreatedAt, HiddenAt, HueColor, Index, RingId};
+
+    fn ring(index: i32, hidden_at: HiddenAt) -> anyhow::Result<Ring> {
+        Ok(Ring::new(
+            RingId::default(),
+            Position::new(132.7666f64, 33.8414f64)?,
+            LocationId::default(),
+            UserId::default(),
+            Index::new(index)?,
+            HueColor::new(index),
+            CreatedAt::new(OffsetDateTime::now_utc()),
+            hidden_at,
+        ))
+    }
+
+    #[test]
+    fn hidden_ring_test() -> anyhow::Result<()> {
+        let shown = ring(0, HiddenAt::default())?;
+        let hidden = ring(1, HiddenAt::new(OffsetDateTime::now_utc()))?;
+        let instance = Instance::new(
+            InstanceId::default(),
+            LocationId::default(),
+            RingSet::new(Capacity::default(), vec![shown.clone(), hidden])?,
+            StartedAt::default(),
+            FinishedAt::default(),
+            Version::default(),
+        );
+
+        let detail = RingInstanceWithDetail::from(instance);
+        assert_eq!(detail.rings.len(), 1);
+        assert!(detail.rings.iter().all(|ring| ring.id() == shown.id()));
+        Ok(())
     }
 }
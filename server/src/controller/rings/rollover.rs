@@ -0,0 +1,20 @@
+use crate::controller::{Exhaust, InstanceFinished};
+use application::transfer::RolloverDto;
+
+pub struct RolloversToEvents;
+
+impl Exhaust<Vec<RolloverDto>> for RolloversToEvents {
+    type To = Vec<InstanceFinished>;
+    fn emit(&self, input: Vec<RolloverDto>) -> Self::To {
+        input
+            .into_iter()
+            .map(|rollover| {
+                InstanceFinished::new(
+                    (*rollover.finished.location()).into(),
+                    rollover.finished.id().clone().into(),
+                    rollover.started.map(Into::into),
+                )
+            })
+            .collect()
+    }
+}
@@ -1,5 +1,5 @@
 use application::error::{ApplicationError, ApplicationErrorKind};
-use axum::http::StatusCode;
+use axum::http::{header, HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use driver::error::DriverError;
@@ -18,8 +18,12 @@ pub enum ServerError {
     Driver(anyhow::Error),
     #[error(transparent)]
     Kernel(anyhow::Error),
+    #[error(transparent)]
+    Conflict(anyhow::Error),
     #[error("Required environment variable not set! `{0}` must set.")]
     EnvError(&'static str),
+    #[error("Invalid environment variable. `{0}` is malformed.")]
+    InvalidEnv(&'static str),
     #[error("Not found, `{entity}`: target `{target}`")]
     NotFound {
         entity: &'static str,
@@ -27,10 +31,28 @@ pub enum ServerError {
     },
     #[error("Invalid Token: {0}")]
     UnAuthorize(KernelError),
+    #[error(transparent)]
+    Forbidden(KernelError),
+    #[error("Invalid command.\n{0}")]
+    InvalidCommand(String),
+    #[error("Too many requests, retry after {retry_after} seconds.")]
+    RateLimited {
+        retry_after: u64,
+    },
+    #[error("Payload too large, it should be up to {limit} bytes.")]
+    PayloadTooLarge {
+        limit: usize,
+    },
+    #[error("Too many connections, connect again later.")]
+    TooManyConnections,
 }
 
 impl IntoResponse for ServerError {
     fn into_response(self) -> Response {
+        let retry_after = match self {
+            ServerError::RateLimited { retry_after } => Some(retry_after),
+            _ => None,
+        };
         let (status, error_name, msg) = match self {
             ServerError::IO(e) => (StatusCode::BAD_REQUEST, "invalid_data".to_string(), e.to_string()),
             ServerError::HandlerInitialization(e) => {
@@ -42,21 +64,37 @@ impl IntoResponse for ServerError {
                 match error {
                     ApplicationError::Kernel(e) => {
                         let KernelErrorKind { kind, error } = e;
-                        (StatusCode::BAD_REQUEST, kind, error.to_string())
+                        let status = match error {
+                            KernelError::Conflict { .. } => StatusCode::CONFLICT,
+                            KernelError::Forbidden { .. } => StatusCode::FORBIDDEN,
+                            _ => StatusCode::BAD_REQUEST,
+                        };
+                        (status, kind, error.to_string())
                     },
                     ApplicationError::NotFound { .. } => (StatusCode::NOT_FOUND, kind, error.to_string()),
                     ApplicationError::Other(_) => (StatusCode::BAD_REQUEST, kind, error.to_string()),
                 }
             },
             ServerError::Kernel(e) => (StatusCode::INTERNAL_SERVER_ERROR, "kernel".to_string(), e.to_string()),
+            ServerError::Conflict(e) => (StatusCode::CONFLICT, "conflict".to_string(), e.to_string()),
             ServerError::EnvError(e) => (StatusCode::INTERNAL_SERVER_ERROR, "env".to_string(), e.to_string()),
+            ServerError::InvalidEnv(e) => (StatusCode::INTERNAL_SERVER_ERROR, "env".to_string(), e.to_string()),
             ServerError::NotFound { .. } => (StatusCode::NOT_FOUND, "not_found".to_string(), self.to_string()),
             ServerError::UnAuthorize(e) => (StatusCode::UNAUTHORIZED, "unauthorized".to_string(), e.to_string()),
+            ServerError::Forbidden(e) => (StatusCode::FORBIDDEN, "forbidden".to_string(), e.to_string()),
+            ServerError::InvalidCommand(e) => (StatusCode::BAD_REQUEST, "command".to_string(), e),
+            ServerError::RateLimited { .. } => (StatusCode::TOO_MANY_REQUESTS, "rate_limited".to_string(), self.to_string()),
+            ServerError::PayloadTooLarge { .. } => (StatusCode::PAYLOAD_TOO_LARGE, "payload_too_large".to_string(), self.to_string()),
+            ServerError::TooManyConnections => (StatusCode::SERVICE_UNAVAILABLE, "too_many_connections".to_string(), self.to_string()),
         };
 
         let json = json!({ "error": error_name, "message": msg });
 
-        (status, Json(json)).into_response()
+        let mut res = (status, Json(json)).into_response();
+        if let Some(secs) = retry_after {
+            res.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        res
     }
 }
 
@@ -75,6 +113,7 @@ impl From<DriverError> for ServerError {
             DriverError::Decoding { .. } => ServerError::Driver(anyhow::Error::new(value)),
             DriverError::S3(e) => ServerError::Driver(e),
             DriverError::Redis(e) => ServerError::Driver(e),
+            DriverError::Conflict { .. } => ServerError::Conflict(anyhow::Error::new(value)),
         }
     }
 }
@@ -89,4 +128,17 @@ impl From<axum::headers::Error> for ServerError {
     fn from(value: axum::headers::Error) -> Self {
         Self::IO(anyhow::Error::new(value))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::ServerError;
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+    use driver::error::DriverError;
+
+    #[test]
+    fn conflict_test() {
+        let res = ServerError::from(DriverError::Conflict { entity: "instance" }).into_response();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+    }
+}
@@ -1,3 +1,5 @@
+mod device;
 mod geojson;
 
+pub use self::device::*;
 pub use self::geojson::*;
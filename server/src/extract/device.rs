@@ -0,0 +1,68 @@
+use crate::error::ServerError;
+use crate::AppHandler;
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::headers::authorization::Bearer;
+use axum::headers::Authorization;
+use axum::http::request::Parts;
+use axum::TypedHeader;
+use kernel::entities::ring::UserId;
+use kernel::entities::token::DeviceToken;
+use kernel::error::KernelError;
+use kernel::security::{DependOnDeviceIdentity, DeviceIdentity};
+
+/// User of the device token in `Authorization: Bearer <token>`, issued by `POST /devices`.
+///
+/// Rejected with `401 Unauthorized` if the header is missing or malformed, as well as if the token is invalid.
+#[derive(Debug, Clone, Copy)]
+pub struct Device(pub UserId);
+
+#[async_trait]
+impl FromRequestParts<AppHandler> for Device {
+    type Rejection = ServerError;
+    async fn from_request_parts(parts: &mut Parts, state: &AppHandler) -> Result<Self, Self::Rejection> {
+        let TypedHeader(auth) = TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| ServerError::UnAuthorize(KernelError::Validation {
+                msg: "`Authorization: Bearer <device token>` is required.",
+            }))?;
+        let user = state
+            .device_identity()
+            .verify(&DeviceToken::new(auth.token()))
+            .map_err(ServerError::UnAuthorize)?;
+        Ok(Device(user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    async fn status(app: &Router, authorization: Option<String>) -> anyhow::Result<StatusCode> {
+        let mut request = Request::get("/");
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        Ok(app.clone().oneshot(request.body(Body::empty())?).await?.status())
+    }
+
+    #[tokio::test]
+    async fn device_test() -> anyhow::Result<()> {
+        let handler = AppHandler::offline().await;
+        let (_, token) = handler.device_identity().issue();
+        let app = Router::new()
+            .route("/", get(|Device(_): Device| async {}))
+            .with_state(handler);
+
+        assert_eq!(status(&app, Some(format!("Bearer {}", token.as_ref()))).await?, StatusCode::OK);
+        assert_eq!(status(&app, None).await?, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&app, Some("Basic dXNlcjpwYXNz".to_string())).await?, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&app, Some("Bearer forged".to_string())).await?, StatusCode::UNAUTHORIZED);
+        Ok(())
+    }
+}
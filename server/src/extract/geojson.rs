@@ -63,7 +63,7 @@ fn geo_json_content_type(headers: &HeaderMap) -> bool {
     };
 
     let is_geo_json_content_type = mime.type_() == "application"
-        && (mime.subtype() == "geo" || mime.suffix().map_or(false, |name| name == "json"));
+        && (mime.subtype() == "geo" || mime.suffix().is_some_and(|name| name == "json"));
 
     is_geo_json_content_type
 }
@@ -1,19 +1,20 @@
 use std::ops::Deref;
 use crate::error::ServerError;
-use application::services::{DependOnCreateEmptyInstanceService, DependOnCreateInstanceService, DependOnCreateLocationService, DependOnCreateRingService, DependOnDeleteLocationService, DependOnExportImageService, DependOnUpdateInstanceService, DependOnUpdateLocationService};
-use driver::database::{ImageDataBase, InstanceDataBase, LocationDataBase, RingDataBase};
-use driver::security::AuthorizeInMemoryInstance;
+use application::services::{DependOnCreateEmptyInstanceService, DependOnCreateInstanceService, DependOnCreateLocationService, DependOnCreateRingService, DependOnDeleteLocationService, DependOnDeleteRingService, DependOnExpireInstanceService, DependOnExportImageService, DependOnHideRingService, DependOnRestoreRingService, DependOnUpdateInstanceService, DependOnUpdateLocationService};
+use driver::database::{AdminKeyDataBase, AuditLogDataBase, ImageDataBase, InstanceDataBase, LocationDataBase, RingDataBase};
+use driver::security::{AuthorizeAdminKey, HmacDeviceIdentity};
 use driver::service::S3ImageStorageService;
 use driver::{DataBaseInitializer, S3Credentials};
 use kernel::finder::DependOnRingFinder;
 use kernel::repository::{
-    DependOnImageRepository, DependOnInstanceRepository, DependOnLocationRepository,
+    DependOnAdminKeyRepository, DependOnAuditLog, DependOnImageRepository, DependOnInstanceRepository, DependOnLocationRepository,
+    DependOnRingRepository,
 };
-use kernel::security::DependOnAuthorizeAdminPolicy;
+use kernel::security::{DependOnAuthorizeAdminPolicy, DependOnDeviceIdentity};
 use kernel::service::DependOnImageExportExternalStorageService;
 use std::sync::Arc;
-use driver::volatile::LocationEtagVolatileDataBase;
-use kernel::volatiles::DependOnLocationETagCache;
+use driver::volatile::{InProcessEventBus, LocationEtagVolatileDataBase, RedisEventBus, RedisPresenceTracker, RedisRateLimiter};
+use kernel::volatiles::{DependOnEventBus, DependOnLocationETagCache, DependOnPresenceTracker, DependOnRateLimiter, EventBus};
 
 pub struct AppHandler {
     inner: Arc<Handler>,
@@ -27,6 +28,16 @@ impl AppHandler {
     }
 }
 
+#[cfg(test)]
+impl AppHandler {
+    /// Handler whose databases are never connected to, for tests that stop short of them.
+    pub(crate) async fn offline() -> AppHandler {
+        Self {
+            inner: Arc::new(Handler::offline().await),
+        }
+    }
+}
+
 impl Clone for AppHandler {
     fn clone(&self) -> Self {
         Self {
@@ -52,9 +63,15 @@ pub struct Handler {
     ins: InstanceDataBase,
     img: ImageDataBase,
     ring: RingDataBase,
-    auth: AuthorizeInMemoryInstance,
+    keys: AdminKeyDataBase,
+    audit: AuditLogDataBase,
+    auth: AuthorizeAdminKey,
+    device: HmacDeviceIdentity,
 
     cache_loc: LocationEtagVolatileDataBase,
+    bus: Box<dyn EventBus>,
+    rate: RedisRateLimiter,
+    presence: RedisPresenceTracker,
 
     s3_images: S3ImageStorageService,
 }
@@ -68,6 +85,11 @@ impl Handler {
         let redis_url = dotenvy::var("REDIS_URL")
             .map_err(|_| ServerError::EnvError(r#"REDIS_URL"#))?;
 
+        let device_secret = dotenvy::var("DEVICE_TOKEN_SECRET")
+            .map_err(|_| ServerError::EnvError(r#"DEVICE_TOKEN_SECRET"#))?;
+        let device = HmacDeviceIdentity::new(device_secret)
+            .map_err(|_| ServerError::InvalidEnv(r#"DEVICE_TOKEN_SECRET"#))?;
+
         let anonymous = dotenvy::var("S3_ANONYMOUS")
             .map(|v| v.parse::<bool>().unwrap_or(false))
             .unwrap_or(false);
@@ -86,14 +108,6 @@ impl Handler {
         let bucket_region = dotenvy::var("S3_BUCKET_REGION")
             .map_err(|_| ServerError::EnvError(r#"S3_BUCKET_REGION"#))?;
 
-        let one_time = kernel::entities::token::AdminToken::default();
-        tracing::info!("+ Admin Token generated.");
-        tracing::info!("| * {:?}", one_time);
-        tracing::info!("| This token is available as long as this instance is active,");
-        tracing::info!(
-            "+ but is immediately discarded and a new token is regenerated when it is restarted."
-        );
-
         let pg_pool = DataBaseInitializer::setup_postgres(pg_url).await?;
         let redis_pool = DataBaseInitializer::setup_redis(redis_url).await?;
 
@@ -110,11 +124,24 @@ impl Handler {
         let loc = LocationDataBase::new(pg_pool.clone());
         let ins = InstanceDataBase::new(pg_pool.clone());
         let img = ImageDataBase::new(pg_pool.clone());
-        let ring = RingDataBase::new(pg_pool);
-        let auth = AuthorizeInMemoryInstance::new(one_time);
+        let ring = RingDataBase::new(pg_pool.clone());
+        let audit = AuditLogDataBase::new(pg_pool.clone());
+        let keys = AdminKeyDataBase::new(pg_pool);
+        let auth = AuthorizeAdminKey::new(keys.clone());
 
         let s3_images = S3ImageStorageService::new(s3_bucket);
 
+        let bus: Box<dyn EventBus> = match dotenvy::var("EVENT_BUS").as_deref() {
+            Ok("redis") | Err(_) => Box::new(RedisEventBus::new(redis_pool.clone())),
+            Ok("local") => {
+                tracing::warn!("+ EVENT_BUS is local. Rings are not shared with other replicas.");
+                Box::new(InProcessEventBus::default())
+            }
+            Ok(_) => return Err(ServerError::InvalidEnv(r#"EVENT_BUS"#)),
+        };
+
+        let rate = RedisRateLimiter::new(redis_pool.clone());
+        let presence = RedisPresenceTracker::new(redis_pool.clone());
         let cache_loc = LocationEtagVolatileDataBase::new(redis_pool);
 
         Ok(Self {
@@ -122,13 +149,49 @@ impl Handler {
             ins,
             img,
             ring,
+            keys,
+            audit,
             auth,
+            device,
             s3_images,
-            cache_loc
+            cache_loc,
+            bus,
+            rate,
+            presence,
         })
     }
 }
 
+#[cfg(test)]
+impl Handler {
+    async fn offline() -> Self {
+        // Nothing listens on port 1, so the first query fails instead of hanging.
+        let pg_pool = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(100))
+            .connect_lazy("postgres://127.0.0.1:1/offline")
+            .unwrap();
+        let redis_pool = DataBaseInitializer::setup_redis("redis://127.0.0.1:1").await.unwrap();
+        let s3_bucket = DataBaseInitializer::setup_localstack("offline", S3Credentials::anonymous().unwrap()).await.unwrap();
+
+        let keys = AdminKeyDataBase::new(pg_pool.clone());
+        Self {
+            loc: LocationDataBase::new(pg_pool.clone()),
+            ins: InstanceDataBase::new(pg_pool.clone()),
+            img: ImageDataBase::new(pg_pool.clone()),
+            ring: RingDataBase::new(pg_pool.clone()),
+            audit: AuditLogDataBase::new(pg_pool),
+            auth: AuthorizeAdminKey::new(keys.clone()),
+            keys,
+            device: HmacDeviceIdentity::new([0; HmacDeviceIdentity::MIN_SECRET_LEN]).unwrap(),
+            s3_images: S3ImageStorageService::new(s3_bucket),
+            cache_loc: LocationEtagVolatileDataBase::new(redis_pool.clone()),
+            bus: Box::new(InProcessEventBus::default()),
+            rate: RedisRateLimiter::new(redis_pool.clone()),
+            presence: RedisPresenceTracker::new(redis_pool),
+        }
+    }
+}
+
 impl DependOnLocationRepository for Handler {
     type LocationRepository = LocationDataBase;
     fn location_repository(&self) -> &Self::LocationRepository {
@@ -143,6 +206,27 @@ impl DependOnLocationETagCache for Handler {
     }
 }
 
+impl DependOnEventBus for Handler {
+    type EventBus = Box<dyn EventBus>;
+    fn event_bus(&self) -> &Self::EventBus {
+        &self.bus
+    }
+}
+
+impl DependOnRateLimiter for Handler {
+    type RateLimiter = RedisRateLimiter;
+    fn rate_limiter(&self) -> &Self::RateLimiter {
+        &self.rate
+    }
+}
+
+impl DependOnPresenceTracker for Handler {
+    type PresenceTracker = RedisPresenceTracker;
+    fn presence_tracker(&self) -> &Self::PresenceTracker {
+        &self.presence
+    }
+}
+
 impl DependOnCreateLocationService for Handler {
     type CreateLocationService = Self;
     fn create_location_service(&self) -> &Self::CreateLocationService {
@@ -200,13 +284,41 @@ impl DependOnCreateEmptyInstanceService for Handler {
     }
 }
 
+impl DependOnExpireInstanceService for Handler {
+    type ExpireInstanceService = Self;
+    fn expire_instance_service(&self) -> &Self::ExpireInstanceService {
+        self
+    }
+}
+
+impl DependOnAdminKeyRepository for Handler {
+    type AdminKeyRepository = AdminKeyDataBase;
+    fn admin_key_repository(&self) -> &Self::AdminKeyRepository {
+        &self.keys
+    }
+}
+
+impl DependOnAuditLog for Handler {
+    type AuditLog = AuditLogDataBase;
+    fn audit_log(&self) -> &Self::AuditLog {
+        &self.audit
+    }
+}
+
 impl DependOnAuthorizeAdminPolicy for Handler {
-    type AuthorizeAdminPolicy = AuthorizeInMemoryInstance;
+    type AuthorizeAdminPolicy = AuthorizeAdminKey;
     fn authorize_admin_policy(&self) -> &Self::AuthorizeAdminPolicy {
         &self.auth
     }
 }
 
+impl DependOnDeviceIdentity for Handler {
+    type DeviceIdentity = HmacDeviceIdentity;
+    fn device_identity(&self) -> &Self::DeviceIdentity {
+        &self.device
+    }
+}
+
 impl DependOnRingFinder for Handler {
     type RingFinder = RingDataBase;
     fn ring_finder(&self) -> &Self::RingFinder {
@@ -214,6 +326,34 @@ impl DependOnRingFinder for Handler {
     }
 }
 
+impl DependOnRingRepository for Handler {
+    type RingRepository = RingDataBase;
+    fn ring_repository(&self) -> &Self::RingRepository {
+        &self.ring
+    }
+}
+
+impl DependOnHideRingService for Handler {
+    type HideRingService = Self;
+    fn hide_ring_service(&self) -> &Self::HideRingService {
+        self
+    }
+}
+
+impl DependOnRestoreRingService for Handler {
+    type RestoreRingService = Self;
+    fn restore_ring_service(&self) -> &Self::RestoreRingService {
+        self
+    }
+}
+
+impl DependOnDeleteRingService for Handler {
+    type DeleteRingService = Self;
+    fn delete_ring_service(&self) -> &Self::DeleteRingService {
+        self
+    }
+}
+
 impl DependOnImageExportExternalStorageService for Handler {
     type ImageExportExternalStorageService = S3ImageStorageService;
     fn image_export_external_storage_service(&self) -> &Self::ImageExportExternalStorageService {
@@ -1,8 +1,11 @@
+pub mod cli;
+pub mod config;
 pub mod controller;
 pub mod error;
-mod extract;
+pub mod extract;
 mod handler;
 pub mod middleware;
 pub mod routes;
+pub mod schedule;
 
 pub use self::handler::*;
@@ -1,10 +1,14 @@
 use axum::extract::DefaultBodyLimit;
-use axum::routing::{get, post};
+use axum::http::Method;
+use axum::routing::{delete, get, patch, post};
 use axum::{Router, Server};
-use server::middleware::simple_auth;
-use server::{routes, AppHandler};
+use kernel::entities::token::Scope;
+use server::middleware::{cors, scoped_auth, security_headers, RouteCorsLayer};
+use server::cli::KeyCommand;
+use server::config::Config;
+use server::{routes, schedule, AppHandler};
 use std::net::SocketAddr;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use tower_http::trace::TraceLayer;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::Layer;
@@ -31,9 +35,33 @@ async fn main() -> anyhow::Result<()> {
         )
         .init();
 
+    if let Some(command) = KeyCommand::parse(std::env::args().skip(1))? {
+        command.run().await?;
+        return Ok(());
+    }
+
+    let config = Config::from_env()?;
     let handler = AppHandler::init().await?;
 
-    let cors = CorsLayer::permissive();
+    routes::socket::configure(&config.socket);
+    routes::rate::configure(&config.rate);
+    routes::images::configure(&config.image);
+    tokio::spawn(routes::socket::relay(handler.clone()));
+    tokio::spawn(routes::socket::relay_presence(handler.clone()));
+
+    if config.expiry.max_age().is_some() || config.expiry.idle().is_some() {
+        tokio::spawn(schedule::expire_instances(
+            handler.clone(),
+            config.expiry,
+            config.expiry_interval,
+        ));
+    }
+
+    let cors = RouteCorsLayer::new(cors(&config.cors)).route(
+        "/locations",
+        [Method::POST, Method::PATCH, Method::DELETE],
+        cors(&config.admin_cors),
+    );
 
     let admin = Router::new()
         .route(
@@ -43,13 +71,29 @@ async fn main() -> anyhow::Result<()> {
                 .delete(routes::del_location),
         )
         .route_layer(axum::middleware::from_fn_with_state(
-            handler.clone(),
-            simple_auth,
+            (handler.clone(), Scope::LocationsWrite),
+            scoped_auth,
+        ));
+
+    let moderation = Router::new()
+        .route("/:id", delete(routes::del_ring))
+        .route("/:id/hide", patch(routes::hide_ring))
+        .route("/:id/restore", patch(routes::restore_ring))
+        .route_layer(axum::middleware::from_fn_with_state(
+            (handler.clone(), Scope::RingsModerate),
+            scoped_auth,
+        ));
+
+    let audit = Router::new()
+        .route("/", get(routes::audit))
+        .route_layer(axum::middleware::from_fn_with_state(
+            (handler.clone(), Scope::AuditRead),
+            scoped_auth,
         ));
 
     let image = Router::new()
         .route("/", post(routes::reg_images))
-        .layer(DefaultBodyLimit::disable());
+        .layer(DefaultBodyLimit::max(config.image.body_limit()));
 
     let socket = Router::new()
         .route("/", get(routes::socket::ws_handler));
@@ -58,9 +102,15 @@ async fn main() -> anyhow::Result<()> {
         .route("/locations", get(routes::locations))
         .nest("/locations", admin)
         .route("/rings", get(routes::rings).post(routes::reg_ring))
+        .nest("/rings", moderation)
+        .route("/rings/stream", get(routes::socket::ring_stream))
+        .route("/users/me/rings", get(routes::user_rings))
+        .route("/devices", post(routes::reg_device))
+        .nest("/audit", audit)
         .nest("/ws-rings", socket)
         .nest("/images", image)
         .layer(cors)
+        .layer(axum::middleware::from_fn_with_state(config.headers, security_headers))
         .layer(TraceLayer::new_for_http())
         .with_state(handler);
 
@@ -88,18 +138,3 @@ async fn exit() {
         _ = user_interrupt => {}
     }
 }
-
-
-#[cfg(test)]
-mod test {
-    use tower_http::cors::{Any, CorsLayer};
-
-    #[test]
-    fn cors() {
-        let cors = CorsLayer::new()
-            .allow_methods(Any)
-            .allow_headers(Any)
-            .allow_origin(Any);
-        println!("{:?}", cors);
-    }
-}
\ No newline at end of file
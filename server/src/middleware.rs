@@ -1,3 +1,7 @@
 mod authorize;
+mod cors;
+mod headers;
 
 pub use self::authorize::*;
+pub use self::cors::*;
+pub use self::headers::*;
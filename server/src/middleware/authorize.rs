@@ -7,20 +7,32 @@ use axum::http::Request;
 use axum::middleware::Next;
 use axum::response::Response;
 use axum::TypedHeader;
-use kernel::entities::token::AdminToken;
+use kernel::entities::token::{AdminToken, Scope};
+use kernel::error::KernelError;
 use kernel::security::{AuthorizeAdminPolicy, DependOnAuthorizeAdminPolicy};
 
-pub async fn simple_auth<B>(
-    State(handler): State<AppHandler>,
+/// Let the request through if its admin key grants the [`Scope`] given along with the state.
+///
+/// The [`AdminKeyId`](kernel::entities::token::AdminKeyId) of the key is put in the request extensions, as the actor of the request.
+///
+/// ```ignore
+/// .route_layer(axum::middleware::from_fn_with_state((handler.clone(), Scope::RingsModerate), scoped_auth))
+/// ```
+pub async fn scoped_auth<B>(
+    State((handler, scope)): State<(AppHandler, Scope)>,
     TypedHeader(auth): TypedHeader<Authorization<Bearer>>,
-    request: Request<B>,
+    mut request: Request<B>,
     next: Next<B>,
 ) -> Result<Response, ServerError> {
-    handler
+    let actor = handler
         .authorize_admin_policy()
-        .authorize(&AdminToken::new(auth.token()))
+        .authorize(&AdminToken::new(auth.token()), &scope)
         .await
-        .map_err(ServerError::UnAuthorize)?;
+        .map_err(|e| match e {
+            KernelError::Forbidden { .. } => ServerError::Forbidden(e),
+            _ => ServerError::UnAuthorize(e),
+        })?;
+    request.extensions_mut().insert(actor);
 
     let res = next.run(request).await;
     Ok(res)
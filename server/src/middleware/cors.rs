@@ -0,0 +1,178 @@
+use crate::config::CorsConfig;
+use axum::http::header::{ACCESS_CONTROL_REQUEST_METHOD, ETAG, RETRY_AFTER};
+use axum::http::{Method, Request};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tower_http::cors::{AllowOrigin, Cors, CorsLayer};
+
+/// Build the [`CorsLayer`] of a policy.
+pub fn cors(config: &CorsConfig) -> CorsLayer {
+    let origin = match &config.origins {
+        Some(origins) => AllowOrigin::list(origins.iter().cloned()),
+        None => AllowOrigin::any(),
+    };
+    CorsLayer::new()
+        .allow_origin(origin)
+        .allow_methods(config.methods.clone())
+        .allow_headers(config.headers.clone())
+        .allow_credentials(config.credentials)
+        .expose_headers([ETAG, RETRY_AFTER])
+}
+
+/// CORS with a different policy for some methods of a path.
+///
+/// A path has a single `OPTIONS` route even if its methods are split across routers,
+/// so the policy is chosen here by the method a request, or its preflight, is for.
+///
+/// ```ignore
+/// .layer(RouteCorsLayer::new(cors(&config.cors)).route("/locations", [Method::POST], cors(&config.admin_cors)))
+/// ```
+#[derive(Clone)]
+pub struct RouteCorsLayer {
+    default: CorsLayer,
+    routes: Vec<(&'static str, Vec<Method>, CorsLayer)>,
+}
+
+impl RouteCorsLayer {
+    pub fn new(default: CorsLayer) -> Self {
+        Self {
+            default,
+            routes: Vec::new(),
+        }
+    }
+
+    pub fn route(mut self, path: &'static str, methods: impl Into<Vec<Method>>, layer: CorsLayer) -> Self {
+        self.routes.push((path, methods.into(), layer));
+        self
+    }
+}
+
+impl<S: Clone> Layer<S> for RouteCorsLayer {
+    type Service = RouteCors<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RouteCors {
+            default: self.default.layer(inner.clone()),
+            routes: self
+                .routes
+                .iter()
+                .map(|(path, methods, layer)| (*path, methods.clone(), layer.layer(inner.clone())))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RouteCors<S> {
+    default: Cors<S>,
+    routes: Vec<(&'static str, Vec<Method>, Cors<S>)>,
+}
+
+impl<S, B> Service<Request<B>> for RouteCors<S>
+where
+    Cors<S>: Service<Request<B>>,
+{
+    type Response = <Cors<S> as Service<Request<B>>>::Response;
+    type Error = <Cors<S> as Service<Request<B>>>::Error;
+    type Future = <Cors<S> as Service<Request<B>>>::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        for (_, _, cors) in self.routes.iter_mut() {
+            if cors.poll_ready(cx)?.is_pending() {
+                return Poll::Pending;
+            }
+        }
+        self.default.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let method = match request.headers().get(ACCESS_CONTROL_REQUEST_METHOD) {
+            Some(requested) if request.method() == Method::OPTIONS => {
+                Method::from_bytes(requested.as_bytes()).ok()
+            }
+            _ => Some(request.method().clone()),
+        };
+        let route = self.routes.iter_mut().find(|(path, methods, _)| {
+            *path == request.uri().path() && matches!(&method, Some(method) if methods.contains(method))
+        });
+        match route {
+            Some((_, _, cors)) => cors.call(request),
+            None => self.default.call(request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cors, RouteCorsLayer};
+    use crate::config::CorsConfig;
+    use axum::body::Body;
+    use axum::http::header::{
+        ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN,
+    };
+    use axum::http::{HeaderValue, Method, Request, StatusCode};
+    use axum::routing::{get, post};
+    use axum::Router;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let config = CorsConfig {
+            origins: Some(vec![HeaderValue::from_static("https://ring.example.com")]),
+            ..CorsConfig::default()
+        };
+        let admin = CorsConfig {
+            origins: Some(vec![HeaderValue::from_static("https://admin.example.com")]),
+            credentials: true,
+            ..CorsConfig::default()
+        };
+        let locations = Router::new().route("/", post(|| async { StatusCode::CREATED }));
+        Router::new()
+            .route("/locations", get(|| async { StatusCode::OK }))
+            .nest("/locations", locations)
+            .layer(RouteCorsLayer::new(cors(&config)).route(
+                "/locations",
+                [Method::POST, Method::PATCH, Method::DELETE],
+                cors(&admin),
+            ))
+    }
+
+    async fn preflight(origin: &'static str, method: Method) -> anyhow::Result<Option<HeaderValue>> {
+        let req = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/locations")
+            .header(ORIGIN, origin)
+            .header(ACCESS_CONTROL_REQUEST_METHOD, method.as_str())
+            .body(Body::empty())?;
+        let res = app().oneshot(req).await?;
+        assert!(res.status().is_success());
+        Ok(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).cloned())
+    }
+
+    #[tokio::test]
+    async fn preflight_test() -> anyhow::Result<()> {
+        let allowed = preflight("https://ring.example.com", Method::GET).await?;
+        assert_eq!(allowed, Some(HeaderValue::from_static("https://ring.example.com")));
+        assert_eq!(preflight("https://evil.example.com", Method::GET).await?, None);
+
+        // The admin mutations accept the admin origin only.
+        let allowed = preflight("https://admin.example.com", Method::POST).await?;
+        assert_eq!(allowed, Some(HeaderValue::from_static("https://admin.example.com")));
+        assert_eq!(preflight("https://ring.example.com", Method::POST).await?, None);
+        assert_eq!(preflight("https://admin.example.com", Method::GET).await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn request_test() -> anyhow::Result<()> {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/locations")
+            .header(ORIGIN, "https://admin.example.com")
+            .body(Body::empty())?;
+        let res = app().oneshot(req).await?;
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN), Some(&HeaderValue::from_static("https://admin.example.com")));
+        assert_eq!(res.headers().get(ACCESS_CONTROL_ALLOW_CREDENTIALS), Some(&HeaderValue::from_static("true")));
+        Ok(())
+    }
+}
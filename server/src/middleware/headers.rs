@@ -0,0 +1,73 @@
+use crate::config::HeadersConfig;
+use axum::extract::State;
+use axum::http::header::{
+    CONTENT_SECURITY_POLICY, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS,
+    X_FRAME_OPTIONS,
+};
+use axum::http::{HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+
+/// Add the standard security headers to the response, unless a route has set them.
+///
+/// The API only serves data, so nothing is allowed to be loaded or framed from it.
+pub async fn security_headers<B>(
+    State(config): State<HeadersConfig>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let mut res = next.run(request).await;
+    let headers = res.headers_mut();
+    headers
+        .entry(X_CONTENT_TYPE_OPTIONS)
+        .or_insert(HeaderValue::from_static("nosniff"));
+    headers
+        .entry(X_FRAME_OPTIONS)
+        .or_insert(HeaderValue::from_static("DENY"));
+    headers
+        .entry(REFERRER_POLICY)
+        .or_insert(HeaderValue::from_static("no-referrer"));
+    headers
+        .entry(CONTENT_SECURITY_POLICY)
+        .or_insert(HeaderValue::from_static("default-src 'none'; frame-ancestors 'none'"));
+    if let Some(max_age) = config.hsts_max_age {
+        let hsts = format!("max-age={}; includeSubDomains", max_age.as_secs());
+        if let Ok(hsts) = HeaderValue::try_from(hsts) {
+            headers.entry(STRICT_TRANSPORT_SECURITY).or_insert(hsts);
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::security_headers;
+    use crate::config::HeadersConfig;
+    use axum::body::Body;
+    use axum::http::header::{STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS};
+    use axum::http::{HeaderValue, Request};
+    use axum::routing::get;
+    use axum::Router;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn headers_test() -> anyhow::Result<()> {
+        let config = HeadersConfig {
+            hsts_max_age: Some(Duration::from_secs(31_536_000)),
+        };
+        let app = Router::new()
+            .route("/", get(|| async { ([(X_FRAME_OPTIONS, "SAMEORIGIN")], "ok") }))
+            .layer(axum::middleware::from_fn_with_state(config, security_headers));
+
+        let res = app.oneshot(Request::get("/").body(Body::empty())?).await?;
+        let headers = res.headers();
+        assert_eq!(headers.get(X_CONTENT_TYPE_OPTIONS), Some(&HeaderValue::from_static("nosniff")));
+        assert_eq!(headers.get(X_FRAME_OPTIONS), Some(&HeaderValue::from_static("SAMEORIGIN")));
+        assert_eq!(
+            headers.get(STRICT_TRANSPORT_SECURITY),
+            Some(&HeaderValue::from_static("max-age=31536000; includeSubDomains"))
+        );
+        Ok(())
+    }
+}
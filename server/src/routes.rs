@@ -1,8 +1,13 @@
-mod images;
+mod audit;
+mod devices;
+pub mod images;
 mod locations;
+pub mod rate;
 mod rings;
 pub mod socket;
 
+pub use self::audit::*;
+pub use self::devices::*;
 pub use self::images::*;
 pub use self::locations::*;
 pub use self::rings::*;
@@ -0,0 +1,66 @@
+use crate::controller::{AuditPageToJson, AuditQueryToPage, Controller, TryIntake};
+use crate::error::ServerError;
+use crate::AppHandler;
+use axum::extract::{Query, State};
+use axum::response::IntoResponse;
+use axum::Json;
+use kernel::external::time::OffsetDateTime;
+use kernel::external::uuid::Uuid;
+use kernel::repository::{AuditLog, DependOnAuditLog};
+use serde::Deserialize;
+
+/// A page of the audit log, narrowed down by the rest.
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    /// `next` of the previous page.
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    /// Id of the admin key that made the change.
+    pub actor: Option<Uuid>,
+    /// e.g. `location.update`
+    pub action: Option<String>,
+    /// Id of the changed location or ring.
+    pub target: Option<Uuid>,
+    #[serde(default, with = "kernel::external::time::serde::iso8601::option")]
+    pub since: Option<OffsetDateTime>,
+    #[serde(default, with = "kernel::external::time::serde::iso8601::option")]
+    pub until: Option<OffsetDateTime>,
+}
+
+pub async fn audit(
+    State(handler): State<AppHandler>,
+    Query(query): Query<AuditQuery>,
+) -> Result<impl IntoResponse, ServerError> {
+    let (filter, cursor, limit) = AuditQueryToPage.emit(query)?;
+    let res = Controller::new((), AuditPageToJson)
+        .bypass(|| async {
+            handler.audit_log().find_page(&filter, cursor.as_ref(), &limit).await
+        })
+        .await?;
+    Ok(Json(res))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AuditQuery;
+    use crate::controller::{AuditQueryToPage, TryIntake};
+    use axum::extract::Query;
+    use axum::http::Uri;
+    use kernel::entities::audit::AuditAction;
+
+    #[test]
+    fn parse_test() -> anyhow::Result<()> {
+        let uri: Uri = "/audit?action=location.update&since=2023-11-01T00:00:00Z&target=4a8f7a5e-6a51-4c8e-9f55-6f3f1f6e7c11".parse()?;
+        let Query(query) = Query::<AuditQuery>::try_from_uri(&uri)?;
+        let (filter, cursor, _) = AuditQueryToPage.emit(query)?;
+        assert_eq!(filter.action(), &Some(AuditAction::LocationUpdate));
+        assert!(filter.target().is_some() && filter.actor().is_none());
+        assert!(filter.recorded().since().is_some());
+        assert!(cursor.is_none());
+
+        let uri: Uri = "/audit?action=location.rename".parse()?;
+        let Query(query) = Query::<AuditQuery>::try_from_uri(&uri)?;
+        assert!(AuditQueryToPage.emit(query).is_err());
+        Ok(())
+    }
+}
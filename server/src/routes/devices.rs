@@ -0,0 +1,21 @@
+use crate::controller::{Controller, IssuedDeviceToResponse};
+use crate::error::ServerError;
+use crate::routes::rate;
+use crate::AppHandler;
+use axum::extract::{ConnectInfo, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use kernel::security::{DependOnDeviceIdentity, DeviceIdentity};
+use std::net::SocketAddr;
+
+pub async fn reg_device(
+    State(handler): State<AppHandler>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+) -> Result<impl IntoResponse, ServerError> {
+    rate::admit_device(&handler, &addr.ip()).await?;
+    let res = Controller::new((), IssuedDeviceToResponse)
+        .bypass(|| async { Ok::<_, ServerError>(handler.device_identity().issue()) })
+        .await?;
+    Ok((StatusCode::CREATED, Json(res)))
+}
@@ -1,19 +1,257 @@
-use crate::controller::{Controller, CreateImageRequest, CreateImageRequestToCreateImageDto};
+use crate::config::ImageConfig;
+use crate::controller::{
+    Controller, CreateImageRequest, CreateImageRequestToCreateImageDto, ImageUpload,
+    ImageUploadToCreateImageDto,
+};
 use crate::error::ServerError;
+use crate::extract::Device;
 use crate::AppHandler;
 use application::services::{DependOnExportImageService, ExportImageService};
-use axum::extract::State;
-use axum::http::StatusCode;
+use axum::body::Body;
+use axum::extract::{FromRequest, Multipart, State};
+use axum::http::{header, HeaderMap, Request, StatusCode};
 use axum::response::IntoResponse;
 use axum::Json;
+use kernel::external::time::format_description::well_known::Iso8601;
+use kernel::external::time::OffsetDateTime;
+use kernel::external::uuid::Uuid;
+use once_cell::sync::OnceCell;
 
+static IMAGE: OnceCell<ImageConfig> = OnceCell::new();
+
+/// Apply the settings of the image upload.
+///
+/// Call it before serving, later calls have no effect.
+pub fn configure(config: &ImageConfig) {
+    let _ = IMAGE.set(config.clone());
+}
+
+fn max_bytes() -> usize {
+    IMAGE.get().cloned().unwrap_or_default().max_bytes
+}
+
+/// Attach an image to a ring, either as `multipart/form-data` or as JSON with the image in base64.
+///
+/// The multipart form takes the fields `ring_id`, `created_at` and `image`, in which the image is a binary part.
 pub async fn reg_images(
     State(handler): State<AppHandler>,
-    Json(request): Json<CreateImageRequest>,
+    Device(user): Device,
+    request: Request<Body>,
 ) -> Result<impl IntoResponse, ServerError> {
-    Controller::new(CreateImageRequestToCreateImageDto, ())
-        .intake(request)
-        .bypass(|input| async { handler.export_image_service().export(input).await })
-        .await?;
+    let max_bytes = max_bytes();
+    if is_multipart(request.headers()) {
+        let multipart = Multipart::from_request(request, &())
+            .await
+            .map_err(|e| ServerError::IO(anyhow::Error::new(e)))?;
+        let upload = read_upload(multipart, max_bytes).await?;
+        Controller::new(ImageUploadToCreateImageDto, ())
+            .intake((user, upload))
+            .bypass(|input| async { handler.export_image_service().export(input).await })
+            .await?;
+    } else {
+        let Json(request) = Json::<CreateImageRequest>::from_request(request, &())
+            .await
+            .map_err(|e| match e.status() {
+                StatusCode::PAYLOAD_TOO_LARGE => ServerError::PayloadTooLarge { limit: max_bytes },
+                _ => ServerError::IO(anyhow::Error::new(e)),
+            })?;
+        Controller::new(CreateImageRequestToCreateImageDto { max_bytes }, ())
+            .try_intake((user, request))?
+            .bypass(|input| async { handler.export_image_service().export(input).await })
+            .await?;
+    }
     Ok(StatusCode::CREATED)
 }
+
+fn is_multipart(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.parse::<mime::Mime>().ok())
+        .is_some_and(|mime| mime.type_() == mime::MULTIPART && mime.subtype() == mime::FORM_DATA)
+}
+
+/// Read the fields of the multipart form, streaming the image up to `max_bytes`.
+async fn read_upload(mut multipart: Multipart, max_bytes: usize) -> Result<ImageUpload, ServerError> {
+    let invalid = |msg: &'static str| ServerError::IO(anyhow::Error::msg(msg));
+    let read_error = |e: axum::extract::multipart::MultipartError| match e.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => ServerError::PayloadTooLarge { limit: max_bytes },
+        _ => ServerError::IO(anyhow::Error::new(e)),
+    };
+
+    let (mut ring_id, mut created_at, mut image) = (None, None, None);
+    while let Some(mut field) = multipart.next_field().await.map_err(read_error)? {
+        match field.name() {
+            Some("ring_id") => {
+                let text = field.text().await.map_err(read_error)?;
+                ring_id = Some(text.trim().parse::<Uuid>().map_err(|_| invalid("`ring_id` should be an UUID."))?);
+            }
+            Some("created_at") => {
+                let text = field.text().await.map_err(read_error)?;
+                created_at = Some(
+                    OffsetDateTime::parse(text.trim(), &Iso8601::DEFAULT)
+                        .map_err(|_| invalid("`created_at` should be an ISO 8601 date time."))?,
+                );
+            }
+            Some("image") => {
+                let mut bin = Vec::new();
+                while let Some(chunk) = field.chunk().await.map_err(read_error)? {
+                    if bin.len() + chunk.len() > max_bytes {
+                        return Err(ServerError::PayloadTooLarge { limit: max_bytes });
+                    }
+                    bin.extend_from_slice(&chunk);
+                }
+                image = Some(bin);
+            }
+            // Unknown fields are skipped, so that clients can send more in the future.
+            _ => {}
+        }
+    }
+
+    Ok(ImageUpload {
+        ring_id: ring_id.ok_or_else(|| invalid("`ring_id` is required."))?,
+        image: image.ok_or_else(|| invalid("`image` is required."))?,
+        created_at: created_at.ok_or_else(|| invalid("`created_at` is required."))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{configure, read_upload, reg_images};
+    use crate::config::ImageConfig;
+    use crate::error::ServerError;
+    use crate::AppHandler;
+    use axum::body::Body;
+    use axum::extract::{DefaultBodyLimit, FromRequest, Multipart};
+    use axum::http::{header, Request, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use kernel::security::{DependOnDeviceIdentity, DeviceIdentity};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    const BOUNDARY: &str = "wawwd-boundary";
+    const RING_ID: &str = "4a8f7a5e-6a51-4c8e-9f55-6f3f1f6e7c11";
+    const MAX_BYTES: usize = 8;
+
+    fn multipart_body(fields: &[(&str, &str)], image: Option<&[u8]>) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (name, value) in fields {
+            body.extend(format!("--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n").bytes());
+        }
+        if let Some(image) = image {
+            body.extend(format!("--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"ring.png\"\r\nContent-Type: image/png\r\n\r\n").bytes());
+            body.extend_from_slice(image);
+            body.extend("\r\n".bytes());
+        }
+        body.extend(format!("--{BOUNDARY}--\r\n").bytes());
+        body
+    }
+
+    async fn multipart(image: &[u8]) -> anyhow::Result<Multipart> {
+        let body = multipart_body(&[("ring_id", RING_ID), ("created_at", "2023-11-01T00:00:00Z")], Some(image));
+        let req = Request::post("/images")
+            .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={BOUNDARY}"))
+            .body(Body::from(body))?;
+        Ok(Multipart::from_request(req, &()).await?)
+    }
+
+    #[tokio::test]
+    async fn upload_test() -> anyhow::Result<()> {
+        let image = vec![0x89u8, b'P', b'N', b'G', 0, 1, 2, 3];
+        let upload = read_upload(multipart(&image).await?, 8).await?;
+        assert_eq!(upload.image, image);
+        assert_eq!(upload.ring_id.to_string(), RING_ID);
+
+        let res = read_upload(multipart(&image).await?, 7).await;
+        assert!(matches!(res, Err(ServerError::PayloadTooLarge { limit: 7 })));
+        Ok(())
+    }
+
+    /// `/images` as `main` routes it, and a device token for it.
+    async fn app() -> (Router, String) {
+        let config = ImageConfig { max_bytes: MAX_BYTES };
+        configure(&config);
+        let handler = AppHandler::offline().await;
+        let (_, token) = handler.device_identity().issue();
+        let app = Router::new()
+            .route("/images", post(reg_images))
+            .layer(DefaultBodyLimit::max(config.body_limit()))
+            .with_state(handler);
+        (app, format!("Bearer {}", token.as_ref()))
+    }
+
+    async fn send(app: &Router, token: &str, content_type: String, body: Vec<u8>) -> anyhow::Result<(StatusCode, Value)> {
+        let req = Request::post("/images")
+            .header(header::AUTHORIZATION, token)
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(body))?;
+        let res = app.clone().oneshot(req).await?;
+        let status = res.status();
+        let bytes = hyper::body::to_bytes(res.into_body()).await?;
+        Ok((status, serde_json::from_slice(&bytes).unwrap_or(Value::Null)))
+    }
+
+    async fn send_json(app: &Router, token: &str, body: Value) -> anyhow::Result<(StatusCode, Value)> {
+        send(app, token, mime::APPLICATION_JSON.to_string(), serde_json::to_vec(&body)?).await
+    }
+
+    async fn send_multipart(app: &Router, token: &str, fields: &[(&str, &str)], image: Option<&[u8]>) -> anyhow::Result<(StatusCode, Value)> {
+        let content_type = format!("multipart/form-data; boundary={BOUNDARY}");
+        send(app, token, content_type, multipart_body(fields, image)).await
+    }
+
+    #[tokio::test]
+    async fn reg_images_json_test() -> anyhow::Result<()> {
+        let (app, token) = app().await;
+        // "iVBORw==" is 5 bytes, "iVBORw0KGgoA" is 9.
+        let request = |image: &str| json!({ "ring_id": RING_ID, "image": image, "created_at": "2023-11-01T00:00:00Z" });
+
+        // Accepted as before, up to the ring lookup which has no database here.
+        let (status, body) = send_json(&app, &token, request("iVBORw==")).await?;
+        assert_eq!(body["error"], "driver", "{status} {body}");
+
+        // The cap is on the decoded image.
+        let (status, body) = send_json(&app, &token, request("iVBORw0KGgoA")).await?;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["error"], "payload_too_large");
+
+        let (status, body) = send_json(&app, &token, request("not base64!")).await?;
+        assert_eq!((status, &body["error"]), (StatusCode::BAD_REQUEST, &json!("invalid_data")));
+
+        let (status, body) = send_json(&app, &token, json!({ "ring_id": RING_ID, "created_at": "2023-11-01T00:00:00Z" })).await?;
+        assert_eq!((status, &body["error"]), (StatusCode::BAD_REQUEST, &json!("invalid_data")));
+
+        // Bodies over the limit are cut off before they are parsed.
+        let (status, body) = send_json(&app, &token, request(&"A".repeat(128 * 1024))).await?;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["error"], "payload_too_large");
+
+        let (status, _) = send_json(&app, "Bearer forged", request("iVBORw==")).await?;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reg_images_multipart_test() -> anyhow::Result<()> {
+        let (app, token) = app().await;
+        let fields = [("ring_id", RING_ID), ("created_at", "2023-11-01T00:00:00Z")];
+
+        // Read through, up to the ring lookup which has no database here.
+        let (status, body) = send_multipart(&app, &token, &fields, Some(&[0x89, b'P', b'N', b'G'])).await?;
+        assert_eq!(body["error"], "driver", "{status} {body}");
+
+        let (status, body) = send_multipart(&app, &token, &fields, Some(&[0; MAX_BYTES + 1])).await?;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["error"], "payload_too_large");
+
+        let (status, body) = send_multipart(&app, &token, &fields, None).await?;
+        assert_eq!((status, &body["error"]), (StatusCode::BAD_REQUEST, &json!("invalid_data")));
+        assert_eq!(body["message"], "`image` is required.");
+
+        let (status, body) = send_multipart(&app, &token, &fields[..1], Some(&[0x89])).await?;
+        assert_eq!((status, &body["error"]), (StatusCode::BAD_REQUEST, &json!("invalid_data")));
+        assert_eq!(body["message"], "`created_at` is required.");
+        Ok(())
+    }
+}
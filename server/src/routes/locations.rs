@@ -1,8 +1,8 @@
 use std::str::FromStr;
-use crate::controller::form::DeleteRequest;
+use crate::controller::form::{DeleteRequest, NearbyQuery};
 use crate::controller::{
     Controller, DeleteRequestToDeleteLocationDto, GeoJsonToCreateLocationDto,
-    GeoJsonToUpdateLocationDto,
+    GeoJsonToUpdateLocationDto, NearbyQueryToSearch, TryIntake,
 };
 use crate::error::ServerError;
 use crate::extract::GeoJson;
@@ -12,12 +12,14 @@ use application::services::{
     DependOnDeleteLocationService, DependOnUpdateLocationService, UpdateLocationService,
 };
 use application::transfer::{CreateLocationDto, DeleteLocationDto, UpdateLocationDto};
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
-use axum::Json;
+use axum::{Extension, Json};
 use axum::headers::{ETag, HeaderMapExt, IfNoneMatch};
 use geojson::{Feature, FeatureCollection};
+use kernel::entities::token::AdminKeyId;
+use kernel::error::KernelError;
 use kernel::repository::{DependOnLocationRepository, LocationRepository};
 use kernel::volatiles::{DependOnLocationETagCache, LocationETagCache};
 
@@ -26,7 +28,12 @@ use inner::ResType;
 pub async fn locations(
     State(handler): State<AppHandler>,
     header: HeaderMap,
+    Query(query): Query<NearbyQuery>,
 ) -> Result<impl IntoResponse, ServerError> {
+    if let Some(near) = query.near.clone() {
+        return nearby(handler, near, query).await;
+    }
+
     if let Some(etag) = header.typed_try_get::<IfNoneMatch>()? {
         if let Some(cache) = handler.location_e_tag_cache().find().await? {
             println!("{:?}", etag);
@@ -61,12 +68,40 @@ pub async fn locations(
     ))
 }
 
+/// `?near=lon,lat&within=m`, nearest first with `distance` in the properties.
+/// The result depends on the query, so it is not tied to the location `ETag`.
+async fn nearby(
+    handler: AppHandler,
+    near: String,
+    query: NearbyQuery,
+) -> Result<ResType, ServerError> {
+    let (center, within, limit) = NearbyQueryToSearch.emit((near, query))?;
+    let features = handler
+        .location_repository()
+        .find_nearby(&center, &within, &limit)
+        .await?
+        .into_iter()
+        .map(|(loc, distance)| {
+            let mut feature = Feature::try_from(loc)?;
+            feature.set_property("distance", f64::from(distance));
+            Ok(feature)
+        })
+        .collect::<Result<Vec<Feature>, KernelError>>()?;
+
+    Ok(ResType::Ok(
+        StatusCode::OK,
+        None,
+        GeoJson(geojson::GeoJson::FeatureCollection(FeatureCollection::from_iter(features)))
+    ))
+}
+
 pub async fn reg_location(
     State(handler): State<AppHandler>,
+    Extension(actor): Extension<AdminKeyId>,
     GeoJson(geojson): GeoJson,
 ) -> Result<impl IntoResponse, ServerError> {
     Controller::new(GeoJsonToCreateLocationDto, ())
-        .try_intake(geojson)?
+        .try_intake((actor, geojson))?
         .bypass(|input: CreateLocationDto| async {
             handler.create_location_service().create(input).await
         })
@@ -77,10 +112,11 @@ pub async fn reg_location(
 
 pub async fn upd_location(
     State(handler): State<AppHandler>,
+    Extension(actor): Extension<AdminKeyId>,
     GeoJson(geojson): GeoJson,
 ) -> Result<impl IntoResponse, ServerError> {
     Controller::new(GeoJsonToUpdateLocationDto, ())
-        .try_intake(geojson)?
+        .try_intake((actor, geojson))?
         .bypass(|input: UpdateLocationDto| async {
             handler.update_location_service().update(input).await
         })
@@ -91,10 +127,11 @@ pub async fn upd_location(
 
 pub async fn del_location(
     State(handler): State<AppHandler>,
+    Extension(actor): Extension<AdminKeyId>,
     Json(user_input): Json<DeleteRequest>,
 ) -> Result<impl IntoResponse, ServerError> {
     Controller::new(DeleteRequestToDeleteLocationDto, ())
-        .intake(user_input)
+        .intake((actor, user_input))
         .bypass(|input: DeleteLocationDto| async {
             handler.delete_location_service().delete(input).await
         })
@@ -108,6 +145,7 @@ mod inner {
     use axum::response::{IntoResponse, Response};
     use crate::extract::GeoJson;
 
+    #[allow(clippy::large_enum_variant)]
     pub(super) enum ResType {
         NotModified(StatusCode),
         Ok(StatusCode, Option<String>, GeoJson),
@@ -0,0 +1,60 @@
+use std::net::IpAddr;
+use once_cell::sync::OnceCell;
+use kernel::entities::ring::UserId;
+use kernel::entities::volatiles::{Admission, RateKey, RateLimit};
+use kernel::volatiles::{DependOnRateLimiter, RateLimiter};
+use crate::config::RateConfig;
+use crate::error::ServerError;
+use crate::AppHandler;
+
+static RATE: OnceCell<RateConfig> = OnceCell::new();
+
+/// Apply the rate limits of ring creation and device registration.
+///
+/// Call it before serving, later calls have no effect.
+pub fn configure(config: &RateConfig) {
+    let _ = RATE.set(config.clone());
+}
+
+/// Admit a ring created by `user` from `addr`, or fail with [`ServerError::RateLimited`].
+///
+/// The address is counted first, so that a client cycling through users is still caught.
+/// If the limiter itself fails, the ring is admitted.
+pub(crate) async fn admit_ring(handler: &AppHandler, user: &UserId, addr: &IpAddr) -> Result<(), ServerError> {
+    let config = RATE.get_or_init(RateConfig::default);
+    if let Some(limit) = &config.per_addr {
+        admit(handler, &RateKey::addr(addr), limit).await?;
+    }
+    if let Some(limit) = &config.per_user {
+        admit(handler, &RateKey::user(user), limit).await?;
+    }
+    Ok(())
+}
+
+/// Admit a device registered from `addr`, or fail with [`ServerError::RateLimited`].
+///
+/// Each device is a new user, so registering is what a client cycling through users does.
+pub(crate) async fn admit_device(handler: &AppHandler, addr: &IpAddr) -> Result<(), ServerError> {
+    let config = RATE.get_or_init(RateConfig::default);
+    if let Some(limit) = &config.devices_per_addr {
+        admit(handler, &RateKey::device_addr(addr), limit).await?;
+    }
+    Ok(())
+}
+
+async fn admit(handler: &AppHandler, key: &RateKey, limit: &RateLimit) -> Result<(), ServerError> {
+    match handler.rate_limiter().acquire(key, limit).await {
+        Ok(Admission::Allowed) => Ok(()),
+        Ok(Admission::Limited { retry_after }) => {
+            tracing::debug!("`{key}` is rate limited for {retry_after}.");
+            Err(ServerError::RateLimited {
+                // Rounded up, so that retrying right on time succeeds.
+                retry_after: (retry_after.as_seconds_f64().ceil() as u64).max(1),
+            })
+        }
+        Err(e) => {
+            tracing::error!("failed to check the rate limit of `{key}`. {e}");
+            Ok(())
+        }
+    }
+}
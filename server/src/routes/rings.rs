@@ -1,21 +1,34 @@
 mod query;
 
-use self::query::SelectionQuery;
+pub use self::query::SelectionQuery;
 use crate::controller::{
-    Controller, CreateRingRequest, MaybeInstanceToDetailResponse, InstancesToJsonBTreeSet,
-    RequestToCreateRingDto, RingDtoToResponseJson, RingInstance, RingInstanceWithDetail,
+    Controller, CreateRingRequest, HistoryQuery, HistoryQueryToUserPage, InstancePageToJson, MaybeInstanceToDetailResponse,
+    ModeratedRingToResponseJson, RequestToCreateRingDto, RingDtoToResponseJson,
+    RingInstanceWithDetail, RingInstancePage, RingRecordPageToJson,
     SelectionIdToInstanceId,
+    SelectionQueryToInstancePage, ServerMessage,
 };
+use crate::controller::TryIntake;
 use crate::error::ServerError;
+use crate::extract::Device;
+use crate::routes::rate;
+use crate::routes::socket::broadcast;
 use crate::AppHandler;
-use application::services::{CreateRingService, DependOnCreateRingService};
-use axum::extract::{Query, State};
+use application::services::{
+    CreateRingService, DeleteRingService, DependOnCreateRingService, DependOnDeleteRingService,
+    DependOnHideRingService, DependOnRestoreRingService, HideRingService, RestoreRingService,
+};
+use axum::extract::{ConnectInfo, Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use axum::Json;
+use axum::{Extension, Json};
+use kernel::entities::token::AdminKeyId;
+use kernel::external::uuid::Uuid;
+use kernel::finder::{DependOnRingFinder, RingFinder};
 use kernel::repository::{DependOnInstanceRepository, InstanceRepository};
+use kernel::volatiles::{DependOnPresenceTracker, PresenceTracker};
 use serde::Serialize;
-use std::collections::BTreeSet;
+use std::net::SocketAddr;
 
 pub async fn rings(
     State(handler): State<AppHandler>,
@@ -25,35 +38,108 @@ pub async fn rings(
     #[serde(untagged)]
     enum RetType {
         Detail(RingInstanceWithDetail),
-        Set(BTreeSet<RingInstance>),
+        Page(RingInstancePage),
     }
 
     let res = if let Some(id) = query.id {
+        let handler_once = handler.clone();
         let res = Controller::new(SelectionIdToInstanceId, MaybeInstanceToDetailResponse)
             .intake(id)
-            .handle(|input| async move { handler.instance_repository().find_by_id(&input).await })
+            .handle(|input| async move { handler_once.instance_repository().find_by_id(&input).await })
             .await?;
-        RetType::Detail(res.ok_or(ServerError::NotFound {
+        let res = res.ok_or(ServerError::NotFound {
             entity: "Instance (with Details)",
             target: id.to_string(),
-        })?)
+        })?;
+        RetType::Detail(with_viewers(&handler, res).await)
     } else {
-        let res = Controller::new((), InstancesToJsonBTreeSet)
-            .bypass(|| async { handler.instance_repository().find_all().await })
+        let (filter, cursor, limit) = SelectionQueryToInstancePage.emit(query)?;
+        let res = Controller::new((), InstancePageToJson)
+            .bypass(|| async {
+                handler.instance_repository().find_page(&filter, cursor.as_ref(), &limit).await
+            })
             .await?;
-        RetType::Set(res)
+        RetType::Page(res)
     };
 
     Ok(Json(res))
 }
 
+/// Attach the presence count if `instance` is the one currently watched.
+async fn with_viewers(handler: &AppHandler, instance: RingInstanceWithDetail) -> RingInstanceWithDetail {
+    if instance.is_finished() {
+        return instance;
+    }
+    match handler.presence_tracker().count(&instance.location()).await {
+        Ok(viewers) => instance.with_viewers(viewers),
+        Err(e) => {
+            tracing::error!("failed to count viewers. {e}");
+            instance
+        }
+    }
+}
+
+/// Rings of the device, so that nobody can read the history of another.
+pub async fn user_rings(
+    State(handler): State<AppHandler>,
+    Device(user): Device,
+    Query(query): Query<HistoryQuery>,
+) -> Result<impl IntoResponse, ServerError> {
+    let (user, cursor, limit) = HistoryQueryToUserPage.emit((user, query))?;
+    let res = Controller::new((), RingRecordPageToJson)
+        .bypass(|| async {
+            handler.ring_finder().find_by_user(&user, cursor.as_ref(), &limit).await
+        })
+        .await?;
+    Ok(Json(res))
+}
+
 pub async fn reg_ring(
     State(handler): State<AppHandler>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Device(user): Device,
     Json(ctx): Json<CreateRingRequest>,
 ) -> Result<impl IntoResponse, ServerError> {
+    rate::admit_ring(&handler, &user, &addr.ip()).await?;
     let res = Controller::new(RequestToCreateRingDto, RingDtoToResponseJson)
-        .intake(ctx)
+        .intake((user, ctx))
         .handle(|input| async { handler.create_ring_service().create(input).await })
         .await?;
     Ok((StatusCode::CREATED, Json(res)))
 }
+
+pub async fn hide_ring(
+    State(handler): State<AppHandler>,
+    Extension(actor): Extension<AdminKeyId>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ServerError> {
+    let res = Controller::new((), ModeratedRingToResponseJson)
+        .bypass(|| async { handler.hide_ring_service().hide(actor.into(), id).await })
+        .await?;
+    broadcast(&handler, &res.location(), &ServerMessage::RingHidden { ring: res.clone() }).await;
+    Ok(Json(res))
+}
+
+pub async fn restore_ring(
+    State(handler): State<AppHandler>,
+    Extension(actor): Extension<AdminKeyId>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ServerError> {
+    let res = Controller::new((), ModeratedRingToResponseJson)
+        .bypass(|| async { handler.restore_ring_service().restore(actor.into(), id).await })
+        .await?;
+    broadcast(&handler, &res.location(), &ServerMessage::RingRestored { ring: res.clone() }).await;
+    Ok(Json(res))
+}
+
+pub async fn del_ring(
+    State(handler): State<AppHandler>,
+    Extension(actor): Extension<AdminKeyId>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ServerError> {
+    let res = Controller::new((), ModeratedRingToResponseJson)
+        .bypass(|| async { handler.delete_ring_service().delete(actor.into(), id).await })
+        .await?;
+    broadcast(&handler, &res.location(), &ServerMessage::RingDeleted { ring: res.clone() }).await;
+    Ok(Json(res))
+}
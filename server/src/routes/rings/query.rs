@@ -1,7 +1,48 @@
+use kernel::external::time::OffsetDateTime;
 use kernel::external::uuid::Uuid;
 use serde::Deserialize;
 
+/// Either a single instance by `id`, or a page of instances narrowed down by the rest.
 #[derive(Debug, Deserialize)]
 pub struct SelectionQuery {
     pub id: Option<Uuid>,
+    /// `next` of the previous page.
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    pub location: Option<Uuid>,
+    /// Only instances containing a ring placed by the user.
+    pub user: Option<Uuid>,
+    #[serde(default, with = "kernel::external::time::serde::iso8601::option")]
+    pub started_since: Option<OffsetDateTime>,
+    #[serde(default, with = "kernel::external::time::serde::iso8601::option")]
+    pub started_until: Option<OffsetDateTime>,
+    #[serde(default, with = "kernel::external::time::serde::iso8601::option")]
+    pub finished_since: Option<OffsetDateTime>,
+    #[serde(default, with = "kernel::external::time::serde::iso8601::option")]
+    pub finished_until: Option<OffsetDateTime>,
+    /// `true` for finished instances only, `false` for open ones only.
+    pub finished: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SelectionQuery;
+    use axum::extract::Query;
+    use axum::http::Uri;
+
+    #[test]
+    fn parse_test() -> anyhow::Result<()> {
+        let uri: Uri = "/rings?limit=10&finished=false&started_since=2023-11-01T00:00:00Z&location=4a8f7a5e-6a51-4c8e-9f55-6f3f1f6e7c11".parse()?;
+        let Query(query) = Query::<SelectionQuery>::try_from_uri(&uri)?;
+        assert_eq!(query.limit, Some(10));
+        assert_eq!(query.finished, Some(false));
+        assert!(query.started_since.is_some());
+        assert!(query.started_until.is_none());
+        assert!(query.location.is_some());
+
+        let uri: Uri = "/rings".parse()?;
+        let Query(query) = Query::<SelectionQuery>::try_from_uri(&uri)?;
+        assert!(query.id.is_none() && query.cursor.is_none());
+        Ok(())
+    }
 }
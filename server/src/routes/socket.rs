@@ -1,3 +1,5 @@
+mod sse_rings;
 mod ws_rings;
 
+pub use self::sse_rings::*;
 pub use self::ws_rings::*;
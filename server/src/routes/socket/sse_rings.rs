@@ -0,0 +1,217 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use axum::extract::{ConnectInfo, Query, State};
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::Stream;
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use kernel::entities::location::LocationId;
+use kernel::entities::volatiles::SessionId;
+use kernel::external::uuid::Uuid;
+use crate::error::ServerError;
+use crate::AppHandler;
+use super::ws_rings::{join, limiter, presence, Permit};
+
+#[derive(Debug, Deserialize)]
+pub struct StreamQuery {
+    pub location: Uuid,
+}
+
+/// Fields of a serialized envelope that make up the SSE frame.
+#[derive(Deserialize)]
+struct Head {
+    seq: Option<u64>,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// The room of a location as Server-Sent Events, for clients that can not keep a websocket.
+///
+/// Events are named by the message `type`, carry the envelope as data,
+/// and are identified by `seq` so that `Last-Event-ID` resumes like `last_seen_seq` does.
+/// Streams count towards the websocket connection limits and presence.
+pub async fn ring_stream(
+    State(handler): State<AppHandler>,
+    Query(query): Query<StreamQuery>,
+    ConnectInfo(info): ConnectInfo<SocketAddr>,
+    header: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ServerError> {
+    let ctx = Uuid::new_v4();
+    let location = LocationId::new(query.location);
+    let last_seen = header
+        .get("Last-Event-ID")
+        .and_then(|id| id.to_str().ok())
+        .and_then(|id| id.parse::<u64>().ok());
+
+    // Released when the stream is dropped.
+    let Some(permit) = limiter().try_acquire(info.ip()) else {
+        tracing::warn!("`{info}` rejected, too many connections.");
+        return Err(ServerError::TooManyConnections);
+    };
+    let joined = join(&handler, info, location, last_seen).await?;
+    tracing::info!("{info} subscribed {location} by event stream. (context_id: {ctx})");
+
+    let session = SessionId::new(ctx);
+    presence::enter(&handler, location, session).await;
+    let handler_presence = handler.clone();
+    let refresh = tokio::spawn(async move {
+        presence::refresh(&handler_presence, watch::channel(location).1, session).await;
+    });
+
+    let stream = Subscription {
+        handler,
+        who: info,
+        location,
+        receiver: Some(joined.receiver),
+        pending: joined.messages.into(),
+        session,
+        refresh,
+        _permit: permit,
+    };
+
+    Ok(Sse::new(futures::stream::unfold(stream, |mut stream| async move {
+        let message = stream.next().await?;
+        Some((Ok(event(message)), stream))
+    }))
+    .keep_alive(KeepAlive::default()))
+}
+
+struct Subscription {
+    handler: AppHandler,
+    who: SocketAddr,
+    location: LocationId,
+    /// `None` after lagging behind or a reset of the room, until joined again.
+    receiver: Option<Receiver<String>>,
+    pending: VecDeque<String>,
+    session: SessionId,
+    refresh: JoinHandle<()>,
+    _permit: Permit,
+}
+
+impl Subscription {
+    async fn next(&mut self) -> Option<String> {
+        loop {
+            if let Some(message) = self.pending.pop_front() {
+                return Some(message);
+            }
+
+            let Some(receiver) = self.receiver.as_mut() else {
+                match join(&self.handler, self.who, self.location, None).await {
+                    Ok(joined) => {
+                        self.receiver = Some(joined.receiver);
+                        self.pending.extend(joined.messages);
+                        continue;
+                    }
+                    // The client reconnects with `Last-Event-ID`, and gets the error as a response then.
+                    Err(e) => {
+                        tracing::error!("`{}` could not resync {}. {e}", self.who, self.location);
+                        return None;
+                    }
+                }
+            };
+
+            match receiver.recv().await {
+                Ok(message) => return Some(message),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("`{}` lagged behind {skipped} messages, resyncing with a snapshot.", self.who);
+                    self.receiver = None;
+                }
+                // The room was reset.
+                Err(RecvError::Closed) => {
+                    tracing::info!("`{}` watched a room that was reset, resyncing with a snapshot.", self.who);
+                    self.receiver = None;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.refresh.abort();
+        let handler = self.handler.clone();
+        let (location, session) = (self.location, self.session);
+        tokio::spawn(async move {
+            presence::leave(&handler, location, session).await;
+        });
+    }
+}
+
+fn event(message: String) -> Event {
+    let event = Event::default();
+    let event = match serde_json::from_str::<Head>(&message) {
+        Ok(Head { seq, kind }) => {
+            let event = event.event(kind);
+            match seq {
+                Some(seq) => event.id(seq.to_string()),
+                None => event,
+            }
+        }
+        Err(_) => event,
+    };
+    event.data(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use axum::response::IntoResponse;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    async fn frame(event: Event) -> anyhow::Result<String> {
+        let stream = futures::stream::iter([Ok::<_, Infallible>(event)]);
+        let body = Sse::new(stream).into_response().into_body();
+        let bytes = hyper::body::to_bytes(body).await?;
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    #[tokio::test]
+    async fn event_test() -> anyhow::Result<()> {
+        let message = r#"{"v":1,"seq":42,"type":"ring_created","ring":{}}"#;
+        let framed = frame(event(message.to_string())).await?;
+        assert_eq!(framed, format!("event:ring_created\nid:42\ndata:{message}\n\n"));
+
+        // Messages without `seq` leave `Last-Event-ID` as it was.
+        let message = r#"{"v":1,"type":"error","code":"instance_generate"}"#;
+        let framed = frame(event(message.to_string())).await?;
+        assert_eq!(framed, format!("event:error\ndata:{message}\n\n"));
+        Ok(())
+    }
+
+    fn request(location: Uuid, last_seen: Option<u64>) -> anyhow::Result<Request<Body>> {
+        let mut request = Request::get(format!("/?location={location}"));
+        if let Some(seq) = last_seen {
+            request = request.header("Last-Event-ID", seq);
+        }
+        let mut request = request.body(Body::empty())?;
+        request.extensions_mut().insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+        Ok(request)
+    }
+
+    #[tokio::test]
+    async fn stream_test() -> anyhow::Result<()> {
+        let app = Router::new()
+            .route("/", get(ring_stream))
+            .with_state(AppHandler::offline().await);
+
+        // Resuming an empty room needs no instance.
+        let res = app.clone().oneshot(request(Uuid::new_v4(), Some(0))?).await?;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], mime::TEXT_EVENT_STREAM.as_ref());
+
+        // Without the database there is no instance to start from, and no stream either.
+        let res = app.oneshot(request(Uuid::new_v4(), None)?).await?;
+        assert!(!res.status().is_success());
+        assert_ne!(res.headers()[header::CONTENT_TYPE], mime::TEXT_EVENT_STREAM.as_ref());
+        Ok(())
+    }
+}
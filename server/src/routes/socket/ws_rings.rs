@@ -1,19 +1,108 @@
 mod internal;
+mod keepalive;
+mod limit;
+pub(super) mod presence;
+mod room;
 
-use axum::extract::{ConnectInfo, State, WebSocketUpgrade};
+pub(super) use self::internal::{join, limiter};
+pub(super) use self::limit::Permit;
+
+use axum::extract::{ConnectInfo, Query, State, WebSocketUpgrade};
 use axum::headers::UserAgent;
-use axum::response::Response;
+use axum::response::{IntoResponse, Response};
 use axum::TypedHeader;
 use std::net::SocketAddr;
+use serde::Deserialize;
+use kernel::entities::location::LocationId;
 use kernel::external::uuid::Uuid;
+use std::time::Duration;
+use futures::StreamExt;
+use kernel::entities::volatiles::DestructEvent;
+use kernel::entities::token::DeviceToken;
+use kernel::security::{DependOnDeviceIdentity, DeviceIdentity};
+use kernel::volatiles::{DependOnEventBus, EventBus};
+use crate::config::SocketConfig;
+use crate::error::ServerError;
 use crate::AppHandler;
+use crate::controller::{Envelope, ServerMessage};
+
+const RELAY_RETRY: Duration = Duration::from_secs(1);
+
+/// Send `message` to the websocket clients watching `location`, on every server node.
+///
+/// It goes through the [`EventBus`], and reaches the sockets by [`relay`].
+pub(crate) async fn broadcast(handler: &AppHandler, location: &LocationId, message: &ServerMessage) {
+    let payload = match serde_json::to_value(message) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::error!("failed to serialize broadcast message. {e}");
+            return;
+        }
+    };
+    if let Err(e) = handler.event_bus().publish(location, payload).await {
+        tracing::error!("failed to publish broadcast message. {e}");
+    }
+}
+
+/// Apply the room capacity, keepalive and connection limits.
+///
+/// Call it before serving, later calls have no effect.
+pub fn configure(config: &SocketConfig) {
+    internal::configure(config);
+}
+
+/// Deliver the events of the [`EventBus`] to the websocket rooms of this node.
+///
+/// It runs for as long as the server does, subscribing again if the bus drops.
+pub async fn relay(handler: AppHandler) {
+    loop {
+        match handler.event_bus().subscribe().await {
+            Ok(mut events) => {
+                while let Some(event) = events.next().await {
+                    let DestructEvent { location, seq, payload } = event.into_destruct();
+                    match serde_json::to_string(&Envelope::sequenced(seq, payload)) {
+                        Ok(serialized) => internal::rooms().deliver(&location, seq, serialized),
+                        Err(e) => tracing::error!("failed to serialize relayed message. {e}"),
+                    }
+                }
+                tracing::warn!("event bus subscription closed.");
+            }
+            Err(e) => tracing::error!("failed to subscribe event bus. {e}"),
+        }
+        tokio::time::sleep(RELAY_RETRY).await;
+    }
+}
+
+/// Deliver the presence counts to the websocket rooms of this node, apart from the numbered broadcasts.
+///
+/// It runs for as long as the server does, subscribing again if the tracker drops.
+pub async fn relay_presence(handler: AppHandler) {
+    presence::relay(handler).await;
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SocketQuery {
+    /// Location to watch first. The client can switch it later by sending `hello`.
+    pub location: Uuid,
+    /// `seq` of the last broadcast received before reconnecting.
+    pub last_seen_seq: Option<u64>,
+    /// Device token from `POST /devices`. Without it the client can watch but not create rings.
+    pub token: Option<String>,
+}
 
 pub async fn ws_handler(
     State(handler): State<AppHandler>,
+    Query(query): Query<SocketQuery>,
     ws: WebSocketUpgrade,
     agent: Option<TypedHeader<UserAgent>>,
     ConnectInfo(info): ConnectInfo<SocketAddr>,
 ) -> Response {
+    // Rejected before the upgrade, since a wrong token is a client bug rather than something to recover from.
+    let device = match query.token.map(|token| handler.device_identity().verify(&DeviceToken::new(token))).transpose() {
+        Ok(device) => device,
+        Err(e) => return ServerError::UnAuthorize(e).into_response(),
+    };
+
     let user_agent = if let Some(TypedHeader(user_agent)) = agent {
         user_agent.to_string()
     } else {
@@ -22,11 +111,14 @@ pub async fn ws_handler(
 
     tracing::debug!("`{user_agent}` at {info} connected.");
 
+    // Counted before the upgrade, and rejected with a close frame after it so that clients can tell why.
+    let permit = internal::limiter().try_acquire(info.ip());
+
     ws.on_failed_upgrade(|e| {
         tracing::error!("Failed to upgrade websocket: {}", e);
     }).on_upgrade(move |socket| async move {
         let ctx = Uuid::new_v4();
         tracing::info!("`{user_agent}` at {info} websocket upgrade successfully. (context_id: {ctx})");
-        internal::handle(socket, info, handler, ctx).await;
+        internal::handle(socket, info, handler, ctx, LocationId::new(query.location), query.last_seen_seq, device, permit, internal::keepalive_settings()).await;
     })
 }
\ No newline at end of file
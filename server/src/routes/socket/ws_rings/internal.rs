@@ -3,77 +3,257 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use axum::extract::ws::{CloseFrame, Message, WebSocket};
 use futures::{SinkExt, StreamExt};
-use once_cell::sync::Lazy;
-use tokio::sync::broadcast::{self, Sender};
-use tokio::sync::Mutex;
+use once_cell::sync::OnceCell;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::{mpsc, watch, Mutex};
 use tracing::Instrument;
 use application::services::{DependOnCreateEmptyInstanceService, DependOnCreateRingService};
+use kernel::entities::location::LocationId;
+use kernel::entities::ring::UserId;
+use kernel::entities::volatiles::SessionId;
 use kernel::external::uuid::Uuid;
 use kernel::repository::{DependOnInstanceRepository, InstanceRepository};
 use crate::AppHandler;
-use crate::controller::{Controller, CreateRingRequestWithNonce, InstanceToDetailResponse, MaybeInstanceToDetailResponse, RequestToCreateRingDto, RingDtoToDetailResponseJson};
+use crate::controller::{close_code, ClientMessage, Controller, Envelope, ErrorCode, InstanceFinished, InstanceToDetailResponse, MaybeInstanceToDetailResponse, PROTOCOL_VERSION, RequestToCreateRingDto, RingDtoToDetailResponseJson, RingInstanceWithDetail, ServerMessage};
+use crate::config::SocketConfig;
+use crate::error::ServerError;
+use crate::routes::rate;
+use super::keepalive::{self, Activity, Keepalive};
+use super::limit::{Limiter, Permit};
+use super::presence;
+use super::room::Rooms;
 
-static BROADCAST: Lazy<Sender<String>> = Lazy::new(|| broadcast::channel(10).0);
+static ROOMS: OnceCell<Rooms> = OnceCell::new();
+static LIMITER: OnceCell<Arc<Limiter>> = OnceCell::new();
+static KEEPALIVE: OnceCell<Keepalive> = OnceCell::new();
 
-pub async fn handle(socket: WebSocket, who: SocketAddr, handler: AppHandler, ctx: Uuid) {
-    let (sen, mut rec) = socket.split();
-    let arc_sen = Arc::new(Mutex::new(sen));
+const REPLAY_LOG_SIZE: usize = 256;
 
+/// Apply `config` before the first client connects. Later calls have no effect.
+pub(super) fn configure(config: &SocketConfig) {
+    let _ = ROOMS.set(Rooms::new(config.capacity, REPLAY_LOG_SIZE));
+    let _ = LIMITER.set(Arc::new(Limiter::new(config.max_connections, config.max_connections_per_addr)));
+    let _ = KEEPALIVE.set(Keepalive::from(config));
+}
+
+pub(super) fn rooms() -> &'static Rooms {
+    ROOMS.get_or_init(|| Rooms::new(SocketConfig::default().capacity, REPLAY_LOG_SIZE))
+}
+
+pub(in crate::routes::socket) fn limiter() -> &'static Arc<Limiter> {
+    LIMITER.get_or_init(|| {
+        let config = SocketConfig::default();
+        Arc::new(Limiter::new(config.max_connections, config.max_connections_per_addr))
+    })
+}
+
+pub(super) fn keepalive_settings() -> Keepalive {
+    *KEEPALIVE.get_or_init(|| Keepalive::from(&SocketConfig::default()))
+}
+
+/// Subscription to a room, with what the client should receive first.
+pub(in crate::routes::socket) struct Joined {
+    pub receiver: Receiver<String>,
+    /// Whether `messages` are the missed broadcasts rather than a `snapshot`.
+    pub resumed: bool,
+    /// Serialized envelopes, like the ones from `receiver`.
+    pub messages: Vec<String>,
+}
+
+/// Subscribe to the room of `location`, replaying the broadcasts after `last_seen` if possible.
+///
+/// Fails if there is no instance to start from, and it could not be created either.
+pub(in crate::routes::socket) async fn join(handler: &AppHandler, who: SocketAddr, location: LocationId, last_seen: Option<u64>) -> Result<Joined, ServerError> {
+    if let Some((receiver, missed)) = last_seen.and_then(|seen| rooms().resume(&location, seen)) {
+        tracing::debug!("`{who}` resumed {location} with {} missed messages.", missed.len());
+        return Ok(Joined {
+            receiver,
+            resumed: true,
+            messages: missed,
+        });
+    }
+
+    // Subscribe before reading the instance, so that nothing falls between the snapshot and the room.
+    let (receiver, seq) = rooms().subscribe(&location);
+    let instance = current_instance(handler, who, location).await?;
+    let snapshot = Envelope::sequenced(seq, ServerMessage::Snapshot { instance });
+    Ok(Joined {
+        receiver,
+        resumed: false,
+        messages: vec![text(&snapshot)],
+    })
+}
+
+/// Unfinished instance of `location`, it is created if there is none.
+async fn current_instance(handler: &AppHandler, who: SocketAddr, location: LocationId) -> Result<RingInstanceWithDetail, ServerError> {
     let handler_once = handler.clone();
-    let instance = match Controller::new((), MaybeInstanceToDetailResponse)
-        .bypass(|| async move { handler_once.as_ref().instance_repository().find_unfinished().await })
-        .instrument(tracing::debug_span!("initialize", ctx = %ctx))
+    match Controller::new((), MaybeInstanceToDetailResponse)
+        .bypass(|| async move { handler_once.as_ref().instance_repository().find_unfinished(&location).await })
         .await
     {
-        Ok(Some(res)) => res,
+        Ok(Some(res)) => Ok(res),
         _ => {
             let handler_once = handler.clone();
-            tracing::info!("`{who}` request but there were no valid instances.");
-            let Ok(instance) = Controller::new((), InstanceToDetailResponse)
+            tracing::info!("`{who}` request but there were no valid instances in {location}.");
+            Controller::new((), InstanceToDetailResponse)
                 .bypass(|| async move {
                     use application::services::CreateEmptyInstanceService;
-                    handler_once.as_ref().create_empty_instance_service().create().await
+                    handler_once.as_ref().create_empty_instance_service().create(&location).await
                 })
                 .await
-            else {
-                let e = serde_json::to_string(&serde_json::json!({
-                    "error": "instance_generate",
-                    "reason": "Failed generate instance.",
-                    "context_id": &ctx
-                })).unwrap();
-                arc_sen.lock().await.send(Message::Text(e)).await.unwrap();
-                return;
-            };
-            instance
+                .map_err(ServerError::from)
         }
-    };
+    }
+}
 
-    let Ok(serialized) = serde_json::to_string(&instance) else {
+fn text(message: &Envelope<ServerMessage>) -> String {
+    // Why `.unwrap()`?: Because every `ServerMessage` consists of plain serializable values.
+    serde_json::to_string(message).unwrap()
+}
+
+fn encode(message: ServerMessage) -> Message {
+    Message::Text(text(&Envelope::new(message)))
+}
+
+fn error(code: ErrorCode, reason: impl Into<String>, ctx: Uuid, nonce: Option<Uuid>) -> Message {
+    encode(ServerMessage::Error {
+        code,
+        reason: reason.into(),
+        context_id: ctx,
+        nonce,
+        retry_after: None,
+    })
+}
+
+fn join_failed(e: ServerError, who: SocketAddr, ctx: Uuid) -> Message {
+    tracing::error!("`{who}` could not join the room. {e}");
+    error(ErrorCode::InstanceGenerate, "Failed generate instance.", ctx, None)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn handle(socket: WebSocket, who: SocketAddr, handler: AppHandler, ctx: Uuid, location: LocationId, last_seen: Option<u64>, device: Option<UserId>, permit: Option<Permit>, keepalive: Keepalive) {
+    let (sen, mut rec) = socket.split();
+    let arc_sen = Arc::new(Mutex::new(sen));
+
+    // Released when this returns.
+    let Some(_permit) = permit else {
+        tracing::warn!("`{who}` rejected, too many connections.");
+        let _ = keepalive::close(&arc_sen, close_code::TRY_AGAIN_LATER, "too many connections").await;
         return;
     };
+    let activity = Arc::new(Activity::new());
 
-    arc_sen.lock().await
-        .send(Message::Text(serialized))
+    let joined = match join(&handler, who, location, last_seen)
+        .instrument(tracing::debug_span!("initialize", ctx = %ctx))
         .await
-        .unwrap();
+    {
+        Ok(joined) => joined,
+        Err(e) => {
+            let _ = arc_sen.lock().await.send(join_failed(e, who, ctx)).await;
+            return;
+        }
+    };
+
+    {
+        let mut sen = arc_sen.lock().await;
+        let hello = ServerMessage::Hello { context_id: ctx, location: location.into(), resumed: joined.resumed };
+        if sen.send(encode(hello)).await.is_err() {
+            return;
+        }
+        for message in joined.messages {
+            if sen.send(Message::Text(message)).await.is_err() {
+                return;
+            }
+        }
+    }
 
-    let mut brx = BROADCAST.subscribe();
-    let btx = BROADCAST.clone();
+    let session = SessionId::new(ctx);
+    presence::enter(&handler, location, session).await;
+    // Location the client is watching, followed by the presence refresh.
+    let (watching_tx, watching) = watch::channel(location);
+
+    let mut brx = joined.receiver;
+    // Rooms the client switches to by `hello`.
+    let (room_tx, mut room_rx) = mpsc::channel::<(LocationId, Receiver<String>)>(1);
 
     let tx1 = Arc::clone(&arc_sen);
+    let tx_keepalive = Arc::clone(&arc_sen);
     let handler_recv = handler.clone();
+    let activity_recv = Arc::clone(&activity);
     let mut recv_task = tokio::spawn(async move {
         while let Some(Ok(msg)) = rec.next().await {
+            activity_recv.record(&msg);
             if let Message::Text(msg) = msg {
                 tracing::debug!("`{who}` sent: {:?}", msg);
-                let Ok(deserialized) = serde_json::from_str::<CreateRingRequestWithNonce>(&msg) else {
-                    tracing::error!("`{who}` sent invalid JSON: {:?}", msg);
-                    let _ = tx1.lock().await.send(Message::Text("Invalid JSON".to_string())).await;
+                let envelope = match serde_json::from_str::<Envelope<ClientMessage>>(&msg) {
+                    Ok(envelope) => envelope,
+                    Err(e) => {
+                        tracing::error!("`{who}` sent invalid message: {:?}", msg);
+                        let _ = tx1.lock().await.send(error(ErrorCode::InvalidMessage, e.to_string(), ctx, None)).await;
+                        continue;
+                    }
+                };
+                if envelope.v != PROTOCOL_VERSION {
+                    let reason = format!("`v` should be {PROTOCOL_VERSION}.");
+                    let _ = tx1.lock().await.send(error(ErrorCode::UnsupportedVersion, reason, ctx, None)).await;
                     continue;
+                }
+
+                let deserialized = match envelope.message {
+                    ClientMessage::Hello { location, last_seen_seq } => {
+                        let location = LocationId::new(location);
+                        let joined = match join(&handler_recv, who, location, last_seen_seq).await {
+                            Ok(joined) => joined,
+                            Err(e) => {
+                                let _ = tx1.lock().await.send(join_failed(e, who, ctx)).await;
+                                continue;
+                            }
+                        };
+                        {
+                            let mut sen = tx1.lock().await;
+                            for message in joined.messages {
+                                let _ = sen.send(Message::Text(message)).await;
+                            }
+                        }
+                        // Handed over after the replay, so that the new room's broadcasts come after it.
+                        if room_tx.send((location, joined.receiver)).await.is_err() {
+                            break;
+                        }
+                        let previous = watching_tx.send_replace(location);
+                        if previous != location {
+                            presence::leave(&handler_recv, previous, session).await;
+                            presence::enter(&handler_recv, location, session).await;
+                        }
+                        continue;
+                    }
+                    ClientMessage::Ping { nonce } => {
+                        let _ = tx1.lock().await.send(encode(ServerMessage::Ack { nonce })).await;
+                        continue;
+                    }
+                    ClientMessage::CreateRing(deserialized) => deserialized,
                 };
 
+                let nonce = deserialized.nonce;
+                let Some(user) = device else {
+                    let reason = "Connect with a device `token` to create rings.";
+                    let _ = tx1.lock().await.send(error(ErrorCode::Unauthorized, reason, ctx, nonce)).await;
+                    continue;
+                };
+                if let Err(ServerError::RateLimited { retry_after }) = rate::admit_ring(&handler_recv, &user, &who.ip()).await {
+                    tracing::warn!("`{who}` is rate limited.");
+                    let limited = ServerMessage::Error {
+                        code: ErrorCode::RateLimited,
+                        reason: "Too many rings, send again later.".to_string(),
+                        context_id: ctx,
+                        nonce,
+                        retry_after: Some(retry_after),
+                    };
+                    let _ = tx1.lock().await.send(encode(limited)).await;
+                    continue;
+                }
                 let mut res = match Controller::new(RequestToCreateRingDto, RingDtoToDetailResponseJson)
-                    .intake(deserialized.req)
+                    .intake((user, deserialized.req))
                     .handle(|input| async {
                         use application::services::CreateRingService;
                         handler_recv.as_ref().create_ring_service().create(input).await
@@ -83,24 +263,23 @@ pub async fn handle(socket: WebSocket, who: SocketAddr, handler: AppHandler, ctx
                     Ok(res) => res,
                     Err(e) => {
                         tracing::error!("`{who}` sent invalid data: {:?}", e);
-                        let e = e.to_string();
-                        let e = serde_json::to_string(&serde_json::json!({
-                            "error": "invalid_data",
-                            "reason": e,
-                            "context_id": &ctx
-                        })).unwrap();
-                        let _ = tx1.lock().await.send(Message::Text(e)).await;
+                        let _ = tx1.lock().await.send(error(ErrorCode::from(&e), e.to_string(), ctx, nonce)).await;
                         continue;
                     }
                 };
 
-                res.nonce = deserialized.nonce;
+                res.nonce = nonce;
+                let _ = tx1.lock().await.send(encode(ServerMessage::Ack { nonce })).await;
 
-                let Ok(serialized) = serde_json::to_string(&res) else {
-                    continue;
-                };
+                let room = res.location();
+                let finished = res.is_finished().then(|| res.instance());
+                super::broadcast(&handler_recv, &room, &ServerMessage::RingCreated { ring: res }).await;
 
-                let _ = btx.send(serialized);
+                if let Some(instance) = finished {
+                    let next = current_instance(&handler_recv, who, room).await.ok();
+                    let finished = InstanceFinished::new(room.into(), instance, next);
+                    super::broadcast(&handler_recv, &room, &ServerMessage::InstanceFinished(finished)).await;
+                }
             } else if let Message::Close(frame) = msg {
                 match frame {
                     Some(_) => {
@@ -121,19 +300,77 @@ pub async fn handle(socket: WebSocket, who: SocketAddr, handler: AppHandler, ctx
     }.instrument(tracing::debug_span!("recv_task", ctx = %ctx)));
 
 
+    let handler_presence = handler.clone();
+    let handler_leave = handler.clone();
     let mut send_task = tokio::spawn(async move {
-        while let Ok(msg) = brx.recv().await {
-            if arc_sen.lock().await.send(Message::Text(msg)).await.is_err() {
-                tracing::error!("abort task failed to send message.");
-                break;
+        let mut location = location;
+        loop {
+            tokio::select! {
+                msg = brx.recv() => {
+                    let msg = match msg {
+                        Ok(msg) => msg,
+                        Err(e) => {
+                            // The missed broadcasts are already gone from the channel, or the room was reset,
+                            // so start over from the current instance of the room.
+                            match e {
+                                RecvError::Lagged(skipped) => tracing::warn!("`{who}` lagged behind {skipped} messages, resyncing with a snapshot."),
+                                RecvError::Closed => tracing::info!("`{who}` watched a room that was reset, resyncing with a snapshot."),
+                            }
+                            let joined = match join(&handler, who, location, None).await {
+                                Ok(joined) => joined,
+                                Err(e) => {
+                                    let _ = arc_sen.lock().await.send(join_failed(e, who, ctx)).await;
+                                    break;
+                                }
+                            };
+                            brx = joined.receiver;
+                            let mut sen = arc_sen.lock().await;
+                            for message in joined.messages {
+                                let _ = sen.send(Message::Text(message)).await;
+                            }
+                            continue;
+                        }
+                    };
+                    if arc_sen.lock().await.send(Message::Text(msg)).await.is_err() {
+                        tracing::error!("abort task failed to send message.");
+                        break;
+                    }
+                }
+                switched = room_rx.recv() => {
+                    let Some((switched, receiver)) = switched else { break };
+                    location = switched;
+                    brx = receiver;
+                }
             }
         }
     }.instrument(tracing::debug_span!("send_task", ctx = %ctx)));
 
+    let tx2 = Arc::clone(&tx_keepalive);
+    let watching_presence = watching.clone();
+    let mut keepalive_task = tokio::spawn(async move {
+        tokio::select! {
+            _ = keepalive::keepalive(&tx2, &activity, keepalive) => {},
+            _ = presence::refresh(&handler_presence, watching_presence, session) => {},
+        }
+    }.instrument(tracing::debug_span!("keepalive_task", ctx = %ctx)));
+
     tokio::select! {
-        _ = (&mut send_task) => recv_task.abort(),
-        _ = (&mut recv_task) => send_task.abort(),
+        _ = (&mut send_task) => {
+            recv_task.abort();
+            keepalive_task.abort();
+        },
+        _ = (&mut recv_task) => {
+            send_task.abort();
+            keepalive_task.abort();
+        },
+        _ = (&mut keepalive_task) => {
+            send_task.abort();
+            recv_task.abort();
+        },
     }
 
+    let watched = *watching.borrow();
+    presence::leave(&handler_leave, watched, session).await;
+
     tracing::debug!("`{}` disconnected", who);
 }
@@ -0,0 +1,272 @@
+use std::borrow::Cow;
+use std::time::Duration;
+use axum::extract::ws::{CloseFrame, Message, WebSocket};
+use futures::stream::SplitSink;
+use futures::SinkExt;
+use tokio::sync::Mutex;
+use tokio::time::{self, Instant};
+use crate::config::SocketConfig;
+use crate::controller::close_code;
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Keepalive {
+    pub ping_interval: Duration,
+    pub idle_timeout: Option<Duration>,
+}
+
+impl From<&SocketConfig> for Keepalive {
+    fn from(value: &SocketConfig) -> Self {
+        Self {
+            ping_interval: value.ping_interval,
+            idle_timeout: value.idle_timeout,
+        }
+    }
+}
+
+/// What a client has sent lately, recorded by the receiving side.
+pub(super) struct Activity {
+    inner: std::sync::Mutex<State>,
+}
+
+struct State {
+    last_message: Instant,
+    awaiting_pong: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum Verdict {
+    Ping,
+    Close(u16, &'static str),
+}
+
+impl Activity {
+    pub fn new() -> Self {
+        Self {
+            inner: std::sync::Mutex::new(State {
+                last_message: Instant::now(),
+                awaiting_pong: false,
+            }),
+        }
+    }
+
+    /// Any frame proves the connection alive, but only data frames count as the client being active.
+    /// Browsers answer pings on their own, even from a tab nobody looks at.
+    pub fn record(&self, message: &Message) {
+        let mut state = self.inner.lock().unwrap();
+        state.awaiting_pong = false;
+        if matches!(message, Message::Text(_) | Message::Binary(_)) {
+            state.last_message = Instant::now();
+        }
+    }
+
+    fn check(&self, keepalive: &Keepalive, now: Instant) -> Verdict {
+        let mut state = self.inner.lock().unwrap();
+        if state.awaiting_pong {
+            return Verdict::Close(close_code::HEARTBEAT_TIMEOUT, "heartbeat timeout");
+        }
+        if keepalive.idle_timeout.is_some_and(|idle| now.duration_since(state.last_message) >= idle) {
+            return Verdict::Close(close_code::IDLE_TIMEOUT, "idle timeout");
+        }
+        state.awaiting_pong = true;
+        Verdict::Ping
+    }
+}
+
+/// Ping the client every `ping_interval`, until it stops answering or goes idle.
+///
+/// Returns after sending the close frame, or when the client can not be written to anymore.
+pub(super) async fn keepalive(sender: &Mutex<SplitSink<WebSocket, Message>>, activity: &Activity, keepalive: Keepalive) {
+    let mut ticker = time::interval_at(Instant::now() + keepalive.ping_interval, keepalive.ping_interval);
+    loop {
+        let now = ticker.tick().await;
+        match activity.check(&keepalive, now) {
+            Verdict::Ping => {
+                if sender.lock().await.send(Message::Ping(Vec::new())).await.is_err() {
+                    return;
+                }
+            }
+            Verdict::Close(code, reason) => {
+                tracing::debug!("closing websocket by {reason}.");
+                let _ = close(sender, code, reason).await;
+                return;
+            }
+        }
+    }
+}
+
+pub(super) async fn close(sender: &Mutex<SplitSink<WebSocket, Message>>, code: u16, reason: &'static str) -> Result<(), axum::Error> {
+    let mut sender = sender.lock().await;
+    sender.send(Message::Close(Some(CloseFrame { code, reason: Cow::from(reason) }))).await?;
+    sender.close().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::socket::ws_rings::internal;
+    use crate::routes::socket::ws_rings::limit::Limiter;
+    use crate::AppHandler;
+    use axum::extract::{ConnectInfo, State, WebSocketUpgrade};
+    use axum::response::Response;
+    use axum::routing::get;
+    use axum::Router;
+    use futures::StreamExt;
+    use kernel::entities::location::LocationId;
+    use kernel::external::uuid::Uuid;
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use tokio_tungstenite::tungstenite::Message as Client;
+
+    #[test]
+    fn verdict_test() {
+        let settings = Keepalive {
+            ping_interval: Duration::from_secs(30),
+            idle_timeout: Some(Duration::from_secs(90)),
+        };
+        let activity = Activity::new();
+        let start = Instant::now();
+
+        assert_eq!(activity.check(&settings, start), Verdict::Ping);
+        activity.record(&Message::Pong(Vec::new()));
+        assert_eq!(activity.check(&settings, start + Duration::from_secs(30)), Verdict::Ping);
+        assert_eq!(
+            activity.check(&settings, start + Duration::from_secs(60)),
+            Verdict::Close(close_code::HEARTBEAT_TIMEOUT, "heartbeat timeout")
+        );
+
+        // Pongs keep the connection, but not the client, alive.
+        activity.record(&Message::Pong(Vec::new()));
+        assert_eq!(
+            activity.check(&settings, Instant::now() + Duration::from_secs(90)),
+            Verdict::Close(close_code::IDLE_TIMEOUT, "idle timeout")
+        );
+        activity.record(&Message::Text("{}".to_string()));
+        assert_eq!(activity.check(&settings, Instant::now()), Verdict::Ping);
+    }
+
+    /// `ws_handler` without the query and the token, resuming an empty room so that no database is reached.
+    async fn socket(
+        State((handler, limiter, settings)): State<(AppHandler, Arc<Limiter>, Keepalive)>,
+        ws: WebSocketUpgrade,
+        ConnectInfo(info): ConnectInfo<SocketAddr>,
+    ) -> Response {
+        let permit = limiter.try_acquire(info.ip());
+        ws.on_upgrade(move |socket| {
+            internal::handle(socket, info, handler, Uuid::new_v4(), LocationId::default(), Some(0), None, permit, settings)
+        })
+    }
+
+    async fn serve(limiter: Limiter, settings: Keepalive) -> anyhow::Result<String> {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let app = Router::new()
+            .route("/", get(socket))
+            .with_state((AppHandler::offline().await, Arc::new(limiter), settings));
+        let server = axum::Server::from_tcp(listener)?
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>());
+        tokio::spawn(server);
+        Ok(format!("ws://{addr}/"))
+    }
+
+    fn ping(nonce: Uuid) -> Client {
+        Client::Text(format!(r#"{{"v":1,"type":"ping","nonce":"{nonce}"}}"#))
+    }
+
+    /// Next text message, skipping the control frames.
+    async fn text_of<S>(client: &mut S) -> anyhow::Result<serde_json::Value>
+    where
+        S: futures::Stream<Item = Result<Client, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        while let Some(msg) = client.next().await {
+            if let Client::Text(text) = msg? {
+                return Ok(serde_json::from_str(&text)?);
+            }
+        }
+        anyhow::bail!("connection closed")
+    }
+
+    async fn close_code_of<S>(client: &mut S) -> Option<u16>
+    where
+        S: futures::Stream<Item = Result<Client, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        while let Some(Ok(msg)) = client.next().await {
+            if let Client::Close(frame) = msg {
+                return frame.map(|frame| frame.code.into());
+            }
+        }
+        None
+    }
+
+    #[tokio::test]
+    async fn heartbeat_test() -> anyhow::Result<()> {
+        let settings = Keepalive {
+            ping_interval: Duration::from_millis(50),
+            idle_timeout: None,
+        };
+        let url = serve(Limiter::new(8, 8), settings).await?;
+
+        // Reading the stream answers pings, so the connection outlives several of them.
+        let (mut client, _) = tokio_tungstenite::connect_async(&url).await?;
+        assert_eq!(text_of(&mut client).await?["type"], "hello");
+        let mut pings = 0;
+        while pings < 3 {
+            match client.next().await {
+                Some(Ok(Client::Ping(_))) => pings += 1,
+                other => panic!("unexpected {other:?}"),
+            }
+        }
+
+        // A client that stops reading never answers, and is dropped at the next ping.
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(close_code_of(&mut client).await, Some(close_code::HEARTBEAT_TIMEOUT));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn idle_test() -> anyhow::Result<()> {
+        let settings = Keepalive {
+            ping_interval: Duration::from_millis(50),
+            idle_timeout: Some(Duration::from_millis(200)),
+        };
+        let url = serve(Limiter::new(8, 8), settings).await?;
+
+        let (mut client, _) = tokio_tungstenite::connect_async(&url).await?;
+        assert_eq!(text_of(&mut client).await?["type"], "hello");
+        let nonce = Uuid::new_v4();
+        client.send(ping(nonce)).await?;
+        let ack = text_of(&mut client).await?;
+        assert_eq!(ack["type"], "ack");
+        assert_eq!(ack["nonce"], nonce.to_string());
+
+        // Answering pings does not count.
+        let started = Instant::now();
+        assert_eq!(close_code_of(&mut client).await, Some(close_code::IDLE_TIMEOUT));
+        assert!(started.elapsed() >= Duration::from_millis(150));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn limit_test() -> anyhow::Result<()> {
+        let settings = Keepalive {
+            ping_interval: Duration::from_secs(30),
+            idle_timeout: None,
+        };
+        let url = serve(Limiter::new(8, 2), settings).await?;
+
+        let (mut first, _) = tokio_tungstenite::connect_async(&url).await?;
+        let (_second, _) = tokio_tungstenite::connect_async(&url).await?;
+        let (mut third, _) = tokio_tungstenite::connect_async(&url).await?;
+        assert_eq!(close_code_of(&mut third).await, Some(close_code::TRY_AGAIN_LATER));
+
+        // A slot is released with the connection.
+        first.close(None).await?;
+        while first.next().await.is_some() {}
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let (mut fourth, _) = tokio_tungstenite::connect_async(&url).await?;
+        assert_eq!(text_of(&mut fourth).await?["type"], "hello");
+        fourth.send(ping(Uuid::new_v4())).await?;
+        assert_eq!(text_of(&mut fourth).await?["type"], "ack");
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+/// Caps the number of websocket and event stream connections, in total and per IP address.
+///
+/// Addresses are counted without the port, as every connection of a client comes from another one.
+pub(in crate::routes::socket) struct Limiter {
+    max_total: usize,
+    max_per_addr: usize,
+    counts: Mutex<Counts>,
+}
+
+#[derive(Default)]
+struct Counts {
+    total: usize,
+    per_addr: HashMap<IpAddr, usize>,
+}
+
+/// A connection counted by [`Limiter`], released on drop.
+pub(in crate::routes::socket) struct Permit {
+    limiter: Arc<Limiter>,
+    addr: IpAddr,
+}
+
+impl Limiter {
+    pub fn new(max_total: usize, max_per_addr: usize) -> Self {
+        Self {
+            max_total,
+            max_per_addr,
+            counts: Mutex::new(Counts::default()),
+        }
+    }
+
+    /// Count a connection from `addr`, or `None` if either cap is reached.
+    pub fn try_acquire(self: &Arc<Self>, addr: IpAddr) -> Option<Permit> {
+        let mut counts = self.counts.lock().unwrap();
+        let from_addr = counts.per_addr.get(&addr).copied().unwrap_or(0);
+        if counts.total >= self.max_total || from_addr >= self.max_per_addr {
+            return None;
+        }
+        counts.total += 1;
+        counts.per_addr.insert(addr, from_addr + 1);
+        Some(Permit {
+            limiter: Arc::clone(self),
+            addr,
+        })
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut counts = self.limiter.counts.lock().unwrap();
+        counts.total -= 1;
+        if let Some(count) = counts.per_addr.get_mut(&self.addr) {
+            *count -= 1;
+            if *count == 0 {
+                counts.per_addr.remove(&self.addr);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn limit_test() {
+        let limiter = Arc::new(Limiter::new(3, 2));
+        let a = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let b = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+        let a1 = limiter.try_acquire(a).unwrap();
+        let _a2 = limiter.try_acquire(a).unwrap();
+        assert!(limiter.try_acquire(a).is_none(), "per address");
+
+        let _b1 = limiter.try_acquire(b).unwrap();
+        assert!(limiter.try_acquire(b).is_none(), "in total");
+
+        drop(a1);
+        let _a3 = limiter.try_acquire(a).unwrap();
+        assert!(limiter.counts.lock().unwrap().per_addr[&a] == 2);
+    }
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+use futures::StreamExt;
+use tokio::sync::watch;
+use kernel::entities::location::LocationId;
+use kernel::entities::volatiles::{DestructPresence, SessionId};
+use kernel::volatiles::{DependOnPresenceTracker, PresenceTracker};
+use crate::controller::{Envelope, ServerMessage};
+use crate::AppHandler;
+use super::internal;
+
+/// How often a session is entered again, well within [`PresenceTracker::TTL`].
+const REFRESH: Duration = Duration::from_secs(30);
+const RELAY_RETRY: Duration = Duration::from_secs(1);
+
+/// Count `session` as watching `location`. The room hears of it by [`relay`].
+pub(in crate::routes::socket) async fn enter(handler: &AppHandler, location: LocationId, session: SessionId) {
+    if let Err(e) = handler.presence_tracker().enter(&location, &session).await {
+        tracing::error!("failed to enter presence of {location}. {e}");
+    }
+}
+
+/// Stop counting `session` as watching `location`. The room hears of it by [`relay`].
+pub(in crate::routes::socket) async fn leave(handler: &AppHandler, location: LocationId, session: SessionId) {
+    if let Err(e) = handler.presence_tracker().leave(&location, &session).await {
+        tracing::error!("failed to leave presence of {location}. {e}");
+    }
+}
+
+/// Keep `session` counted on the location it is currently watching. It never returns.
+pub(in crate::routes::socket) async fn refresh(handler: &AppHandler, watching: watch::Receiver<LocationId>, session: SessionId) {
+    let mut interval = tokio::time::interval(REFRESH);
+    // The first tick completes immediately, and the session has just entered.
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let location = *watching.borrow();
+        if let Err(e) = handler.presence_tracker().enter(&location, &session).await {
+            tracing::error!("failed to refresh presence of {location}. {e}");
+        }
+    }
+}
+
+/// Deliver the changed counts of every node to the rooms of this node.
+///
+/// They go around the event bus, so that they neither take a `seq` nor a slot of the replay log.
+pub(super) async fn relay(handler: AppHandler) {
+    loop {
+        match handler.presence_tracker().subscribe().await {
+            Ok(mut changes) => {
+                while let Some(presence) = changes.next().await {
+                    let DestructPresence { location, viewers } = presence.into_destruct();
+                    let message = ServerMessage::Presence {
+                        location: location.into(),
+                        viewers: viewers.into(),
+                    };
+                    match serde_json::to_string(&Envelope::new(message)) {
+                        Ok(serialized) => internal::rooms().notify(&location, serialized),
+                        Err(e) => tracing::error!("failed to serialize presence message. {e}"),
+                    }
+                }
+                tracing::warn!("presence subscription closed.");
+            }
+            Err(e) => tracing::error!("failed to subscribe presence. {e}"),
+        }
+        tokio::time::sleep(RELAY_RETRY).await;
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use kernel::entities::location::LocationId;
+
+/// Broadcast channels keyed by location, so that clients only receive rings of the location they watch.
+///
+/// Each message sent to a room is numbered, and the latest ones are kept
+/// so that a client reconnecting shortly can [`Rooms::resume`] from the last one it saw.
+pub(super) struct Rooms {
+    capacity: usize,
+    log_size: usize,
+    rooms: Mutex<HashMap<LocationId, Room>>,
+}
+
+struct Room {
+    sender: Sender<String>,
+    /// Sequence number of the latest message, `0` before the first one.
+    seq: u64,
+    log: VecDeque<(u64, String)>,
+}
+
+impl Rooms {
+    pub fn new(capacity: usize, log_size: usize) -> Self {
+        Self {
+            capacity,
+            log_size,
+            rooms: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Subscribe to the room of `location`, along with the sequence number the subscription starts after.
+    pub fn subscribe(&self, location: &LocationId) -> (Receiver<String>, u64) {
+        let mut rooms = self.rooms.lock().unwrap();
+        // A room that has been sent to is kept, so that its numbering and log survive reconnects.
+        rooms.retain(|_, room| room.sender.receiver_count() > 0 || room.seq > 0);
+        let room = self.room(&mut rooms, location);
+        (room.sender.subscribe(), room.seq)
+    }
+
+    /// Subscribe to the room of `location` with the messages after `last_seen`.
+    ///
+    /// Returns `None` if some of them are no longer in the log,
+    /// or `last_seen` is ahead of the room (e.g. the server restarted).
+    pub fn resume(&self, location: &LocationId, last_seen: u64) -> Option<(Receiver<String>, Vec<String>)> {
+        let mut rooms = self.rooms.lock().unwrap();
+        let room = self.room(&mut rooms, location);
+        let oldest = room.log.front().map_or(room.seq + 1, |(seq, _)| *seq);
+        if last_seen > room.seq || last_seen + 1 < oldest {
+            return None;
+        }
+        let missed = room.log.iter()
+            .filter(|(seq, _)| *seq > last_seen)
+            .map(|(_, message)| message.clone())
+            .collect();
+        Some((room.sender.subscribe(), missed))
+    }
+
+    /// Send `message` numbered `seq` to the room of `location`.
+    ///
+    /// `seq` is expected to increase, a message numbered like the latest one is dropped as a duplicate.
+    /// A lower `seq` means the numbering started over (e.g. the bus lost its counter), so the room is reset:
+    /// its log is cleared, and the current subscribers are closed so that they resync with a snapshot.
+    pub fn deliver(&self, location: &LocationId, seq: u64, message: String) {
+        let mut rooms = self.rooms.lock().unwrap();
+        let room = self.room(&mut rooms, location);
+        if seq == room.seq {
+            return;
+        }
+        if seq < room.seq {
+            tracing::warn!("{location} went back from seq {} to {seq}, resetting the room.", room.seq);
+            room.sender = broadcast::channel(self.capacity).0;
+            room.log.clear();
+        }
+
+        room.seq = seq;
+        if room.log.len() >= self.log_size {
+            room.log.pop_front();
+        }
+        room.log.push_back((seq, message.clone()));
+        // Having no subscribers is not an error here.
+        let _ = room.sender.send(message);
+    }
+
+    /// Send `message` to the current subscribers of `location`, without numbering or keeping it.
+    ///
+    /// Nobody is watching a room that does not exist yet, so it is not created.
+    pub fn notify(&self, location: &LocationId, message: String) {
+        let rooms = self.rooms.lock().unwrap();
+        if let Some(room) = rooms.get(location) {
+            // Having no subscribers is not an error here.
+            let _ = room.sender.send(message);
+        }
+    }
+
+    fn room<'a>(&self, rooms: &'a mut HashMap<LocationId, Room>, location: &LocationId) -> &'a mut Room {
+        rooms.entry(*location).or_insert_with(|| Room {
+            sender: broadcast::channel(self.capacity).0,
+            seq: 0,
+            log: VecDeque::with_capacity(self.log_size),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    #[test]
+    fn room_test() {
+        let rooms = Rooms::new(4, 4);
+        let (a, b, c) = (LocationId::default(), LocationId::default(), LocationId::default());
+
+        let (mut rx_a, _) = rooms.subscribe(&a);
+        let (mut rx_b, _) = rooms.subscribe(&b);
+
+        rooms.deliver(&a, 1, "to a".to_string());
+        assert_eq!(rx_a.try_recv().unwrap(), "to a");
+        assert_eq!(rx_b.try_recv(), Err(TryRecvError::Empty));
+
+        let (rx_c, _) = rooms.subscribe(&c);
+        drop(rx_a);
+        drop(rx_c);
+        let (_rx_b2, _) = rooms.subscribe(&b);
+        assert!(rooms.rooms.lock().unwrap().contains_key(&a));
+        assert!(!rooms.rooms.lock().unwrap().contains_key(&c));
+
+        rooms.deliver(&b, 1, "to b".to_string());
+        assert_eq!(rx_b.try_recv().unwrap(), "to b");
+    }
+
+    #[test]
+    fn resume_test() {
+        let rooms = Rooms::new(8, 3);
+        let location = LocationId::default();
+
+        let (_, seq) = rooms.subscribe(&location);
+        assert_eq!(seq, 0);
+        for seq in 1..=5 {
+            rooms.deliver(&location, seq, seq.to_string());
+        }
+        // A duplicate from the bus is ignored.
+        rooms.deliver(&location, 5, "again".to_string());
+        assert_eq!(rooms.subscribe(&location).1, 5);
+
+        // seq 3, 4 and 5 are still in the log.
+        let (_, missed) = rooms.resume(&location, 2).unwrap();
+        assert_eq!(missed, ["3", "4", "5"]);
+        let (_, missed) = rooms.resume(&location, 5).unwrap();
+        assert!(missed.is_empty());
+
+        assert!(rooms.resume(&location, 1).is_none());
+        assert!(rooms.resume(&location, 6).is_none());
+    }
+
+    #[test]
+    fn reset_test() {
+        let rooms = Rooms::new(8, 8);
+        let location = LocationId::default();
+
+        let (mut rx, _) = rooms.subscribe(&location);
+        for seq in 1..=3 {
+            rooms.deliver(&location, seq, seq.to_string());
+        }
+
+        // The numbering started over.
+        rooms.deliver(&location, 1, "reset".to_string());
+        for seq in 1..=3 {
+            assert_eq!(rx.try_recv().unwrap(), seq.to_string());
+        }
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Closed));
+
+        let (mut rx, seq) = rooms.subscribe(&location);
+        assert_eq!(seq, 1);
+        rooms.deliver(&location, 2, "after".to_string());
+        assert_eq!(rx.try_recv().unwrap(), "after");
+
+        // The log holds only the messages after the reset.
+        let (_, missed) = rooms.resume(&location, 0).unwrap();
+        assert_eq!(missed, ["reset", "after"]);
+        assert!(rooms.resume(&location, 3).is_none());
+    }
+
+    #[test]
+    fn notify_test() {
+        let rooms = Rooms::new(8, 8);
+        let location = LocationId::default();
+
+        rooms.notify(&location, "nobody".to_string());
+        assert!(!rooms.rooms.lock().unwrap().contains_key(&location));
+
+        let (mut rx, _) = rooms.subscribe(&location);
+        rooms.deliver(&location, 1, "1".to_string());
+        rooms.notify(&location, "viewers".to_string());
+        rooms.deliver(&location, 2, "2".to_string());
+        for message in ["1", "viewers", "2"] {
+            assert_eq!(rx.try_recv().unwrap(), message);
+        }
+
+        // Neither numbered nor replayed.
+        assert_eq!(rooms.subscribe(&location).1, 2);
+        let (_, missed) = rooms.resume(&location, 0).unwrap();
+        assert_eq!(missed, ["1", "2"]);
+    }
+}
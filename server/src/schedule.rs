@@ -0,0 +1,42 @@
+use crate::controller::{Controller, RolloversToEvents, ServerMessage};
+use crate::routes::socket::broadcast;
+use crate::AppHandler;
+use application::error::ApplicationError;
+use application::services::{DependOnExpireInstanceService, ExpireInstanceService};
+use kernel::entities::instance::Expiry;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+use tracing::Instrument;
+
+/// Roll over expired instances every `interval`, announcing each of them to websocket clients.
+///
+/// It runs for as long as the server does.
+pub async fn expire_instances(handler: AppHandler, expiry: Expiry, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        ticker.tick().await;
+
+        let events = Controller::new((), RolloversToEvents)
+            .bypass(|| async {
+                let expired = handler.expire_instance_service().expire(&expiry).await?;
+                for e in expired.errors {
+                    tracing::error!("failed to roll over an instance: {}", e);
+                }
+                Ok::<_, ApplicationError>(expired.rollovers)
+            })
+            .instrument(tracing::debug_span!("expire_instances"))
+            .await;
+
+        match events {
+            Ok(events) => {
+                for event in events {
+                    tracing::info!("instance rolled over. {:?}", event);
+                    broadcast(&handler, &event.location(), &ServerMessage::InstanceFinished(event)).await;
+                }
+            }
+            Err(e) => tracing::error!("failed to expire instances: {}", e),
+        }
+    }
+}